@@ -0,0 +1,45 @@
+//Cross-check of the release jiras against the ones referenced by the PVT code and the commit messages
+use crate::git::{self, JiraPattern};
+use crate::release::Context;
+
+//warnings for the release jiras the PVT code does not reference and for the jiras referenced by the PVT
+//code or the commit messages that are not part of the release
+pub fn check(ctx: &Context, pattern: &JiraPattern, messages: &[String]) -> Result<Vec<String>, String> {
+    if ctx.pvt_file.is_empty() {
+        return Err("The --check option requires the PVT file, configure the release pvt-markers or use --pvt-file".to_string());
+    }
+    let (start, end) = line_range(&ctx.pvt_line_range)?;
+    let source = git::show(&ctx.pvt_file)?;
+    let lines: Vec<&str> = source.lines().collect();
+    if end > lines.len() {
+        return Err(format!("PVT line range {} is outside {} ({} lines)", ctx.pvt_line_range, ctx.pvt_file, lines.len()));
+    }
+    let location = format!("{}:{}", ctx.pvt_file, ctx.pvt_line_range);
+    let code = pattern.find_all(&lines[start - 1..end].join("\n"));
+    let commits = pattern.find_all(&messages.join("\n"));
+    let released: Vec<&String> = ctx.jiras.iter().chain(&ctx.wip_jiras).collect();
+    let mut warnings: Vec<String> = vec![];
+    for jira in &released {
+        if !code.contains(jira) && commits.contains(jira) {
+            warnings.push(format!("Jira '{}' is not referenced by the PVT code at {}", jira, location));
+        } else if !code.contains(jira) {
+            warnings.push(format!("Jira '{}' is not referenced by the PVT code at {} nor by the commit messages",
+                                  jira, location));
+        }
+    }
+    for jira in code.iter().filter(|jira| !released.contains(jira)) {
+        warnings.push(format!("Jira '{}' referenced by the PVT code at {} is not part of the release", jira, location));
+    }
+    for jira in commits.iter().filter(|jira| !released.contains(jira) && !code.contains(jira)) {
+        warnings.push(format!("Jira '{}' referenced by the commit messages is not part of the release", jira));
+    }
+    Ok(warnings)
+}
+
+//first and last line of a "<start>-<end>" range
+pub fn line_range(range: &str) -> Result<(usize, usize), String> {
+    match range.split_once('-').map(|(start, end)| (start.trim().parse::<usize>(), end.trim().parse::<usize>())) {
+        Some((Ok(start), Ok(end))) if start >= 1 && start <= end => Ok((start, end)),
+        _ => Err(format!("Invalid PVT line range '{}', expected <start>-<end>", range)),
+    }
+}
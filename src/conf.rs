@@ -4,34 +4,300 @@ use serde::Deserialize;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use toml::Value as Toml;
+use toml::value::Table;
 
-use crate::release::Release;
+use crate::release::{ArrayMerge, Release};
+use crate::validate;
 
-#[derive(Deserialize, Debug)]
+const CONF_FILE_NAME: &str = ".templar.toml";
+const SYSTEM_CONF_FILE: &str = "/etc/templar/config.toml";
+const SYSTEM_CONF_ENV: &str = "TEMPLAR_SYSTEM_CONF";
+
+#[derive(Deserialize, Debug, Default)]
 pub struct Conf {
+    #[serde(default)]
     releases: Vec<Release>,
+    #[serde(default)]
+    fragments: Table,
 }
 
 impl Conf {
     pub fn release(&self, name: &str) -> Option<&Release> {
         self.releases.iter().find(|r| r.name.eq(name))
     }
+
+    pub fn releases(&self) -> &Vec<Release> {
+        &self.releases
+    }
+
+    //releases of the layer replace the ones with the same name, fragments are replaced by name
+    fn layer(&mut self, layer: Conf) {
+        for release in layer.releases {
+            match self.releases.iter().position(|r| r.name.eq(&release.name)) {
+                Some(index) => self.releases[index] = release,
+                None => self.releases.push(release),
+            }
+        }
+        self.fragments.extend(layer.fragments);
+    }
+
+    //merges extended templates and included fragments into the content of every template
+    fn resolve(&mut self) -> Result<(), String> {
+        let mut resolved: Vec<Vec<Toml>> = vec![];
+        for release in &self.releases {
+            resolved.push(release.templates().iter()
+                .map(|t| self.resolve_template(&release.name, t.id(), &mut vec![]))
+                .collect::<Result<_, _>>()
+                .map_err(|err| format!("Invalid configuration in '{}': {}", release.origin.display(), err))?);
+        }
+        for (release, contents) in self.releases.iter_mut().zip(resolved) {
+            let conf_dir = release.origin.parent().map(Path::to_path_buf).unwrap_or_default();
+            let schema = release.schema.to_owned();
+            if let Some(tracker) = &mut release.tracker {
+                tracker.resolve(&conf_dir);
+            }
+            if let Some(source) = &mut release.current_version_source {
+                source.resolve(&conf_dir);
+            }
+            release.tweet_file = release.tweet_file.take().map(|file| conf_dir.join(file));
+            for (template, content) in release.templates_mut().iter_mut().zip(contents) {
+                template.resolve(content, schema.as_deref(), &conf_dir);
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_template(&self, release: &str, id: &str, chain: &mut Vec<String>) -> Result<Toml, String> {
+        let key = format!("{}/{}", release, id);
+        if chain.contains(&key) {
+            return Err(format!("Cyclic template inheritance: {} -> {}", chain.join(" -> "), key));
+        }
+        let template = self.release(release).and_then(|r| r.template(id)).ok_or_else(|| match chain.last() {
+            Some(child) => format!("Unknown template '{}' extended by '{}'", key, child),
+            None => format!("Unknown template '{}'", key),
+        })?;
+        chain.push(key.to_owned());
+        let mut content = match template.extends() {
+            Some(parent) => match parent.split_once('/') {
+                Some((parent_release, parent_id)) => self.resolve_template(parent_release, parent_id, chain)?,
+                None => self.resolve_template(release, parent, chain)?,
+            },
+            None => Toml::Table(Table::new()),
+        };
+        for name in template.include() {
+            let fragment = self.fragments.get(name)
+                .ok_or(format!("Unknown fragment '{}' included by '{}'", name, key))?;
+            content = merge(content, fragment.to_owned(), template.array_merge());
+        }
+        chain.pop();
+        Ok(merge(content, template.content().to_owned(), template.array_merge()))
+    }
 }
 
-pub fn init(mut home_dir: PathBuf) -> Result<Conf, String> {
-    home_dir.push(".templar.toml");
-    let conf_file: &Path = home_dir.as_path();
-    if let Ok(mut file) = OpenOptions::new().write(true).create_new(true).open(&conf_file) {
-        let _ = file.write_all(default_conf().as_bytes());
+//tables are merged recursively, arrays are appended or replaced and any other value is replaced
+fn merge(base: Toml, overlay: Toml, arrays: ArrayMerge) -> Toml {
+    match (base, overlay) {
+        (Toml::Table(mut base), Toml::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => {
+                        let merged = merge(std::mem::replace(existing, Toml::Boolean(false)), value, arrays);
+                        *existing = merged;
+                    }
+                    None => { base.insert(key, value); }
+                }
+            }
+            Toml::Table(base)
+        }
+        (Toml::Array(mut base), Toml::Array(overlay)) if arrays == ArrayMerge::Append => {
+            base.extend(overlay);
+            Toml::Array(base)
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+pub fn user_file(home_dir: &Path) -> PathBuf {
+    home_dir.join(CONF_FILE_NAME)
+}
+
+//Configuration files in the order they are layered: system, user, project and explicit files
+pub fn files(home_dir: &Path, current_dir: &Path, explicit: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = vec![];
+    let system_file = std::env::var_os(SYSTEM_CONF_ENV).map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(SYSTEM_CONF_FILE));
+    if system_file.is_file() {
+        files.push(system_file);
+    }
+    let user_file = user_file(home_dir);
+    if user_file.is_file() {
+        files.push(user_file);
+    }
+    if let Some(project_file) = current_dir.ancestors().map(|dir| dir.join(CONF_FILE_NAME)).find(|f| f.is_file()) {
+        files.push(project_file);
+    }
+    for file in explicit {
+        if !file.is_file() {
+            return Err(format!("Configuration file not found: {}", file.display()));
+        }
+        files.push(file.to_owned());
+    }
+    if files.is_empty() {
+        return Err("No configuration found, run 'templar init' to create one".to_string());
     }
-    let content = std::fs::read_to_string(conf_file).map_err(|err|
-        format!("Unable to read configuration file: {}", err.to_string()))?;
-    let conf: Conf = toml::from_str(&content).map_err(|err|
-        format!("Invalid configuration in '{}': {}", conf_file.display(), err.to_string()))?;
+    let mut unique: Vec<PathBuf> = vec![];
+    for file in files {
+        let canonical = file.canonicalize().unwrap_or(file);
+        if !unique.contains(&canonical) {
+            unique.push(canonical);
+        }
+    }
+    Ok(unique)
+}
+
+//every file is read and validated so that the problems of all of them are reported together
+pub fn init(files: &[PathBuf]) -> Result<Conf, String> {
+    let mut sources: Vec<(PathBuf, String)> = vec![];
+    let mut problems: Vec<String> = vec![];
+    for file in files {
+        match std::fs::read_to_string(file) {
+            Ok(content) => sources.push((file.to_owned(), content)),
+            Err(err) => problems.push(format!("Unable to read configuration file '{}': {}", file.display(), err)),
+        }
+    }
+    problems.extend(validate::check(&sources));
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+    let mut conf = Conf::default();
+    for (file, content) in &sources {
+        conf.layer(load(file, content)?);
+    }
+    conf.resolve()?;
     Ok(conf)
 }
 
-fn default_conf() -> String {
+fn load(conf_file: &Path, content: &str) -> Result<Conf, String> {
+    let mut conf: Conf = toml::from_str(content).map_err(|err| validate::toml_error(conf_file, &err))?;
+    for release in conf.releases.iter_mut() {
+        release.origin = conf_file.to_path_buf();
+    }
+    Ok(conf)
+}
+
+//writes the example configuration, an existing file is only replaced when forced
+pub fn create(conf_file: &Path, force: bool) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if force { options.create(true).truncate(true); } else { options.create_new(true); }
+    let mut file = options.open(conf_file).map_err(|err| match err.kind() {
+        std::io::ErrorKind::AlreadyExists =>
+            format!("Configuration file '{}' already exists, use --force to overwrite it", conf_file.display()),
+        _ => format!("Unable to create configuration file '{}': {}", conf_file.display(), err),
+    })?;
+    file.write_all(example_conf().as_bytes()).map_err(|err|
+        format!("Unable to write configuration file '{}': {}", conf_file.display(), err))
+}
+
+fn example_conf() -> String {
     r#"# Templar Configuration
+#
+# Each release has one or more templates whose `content` table is printed as json by:
+#   templar release example -c 1.0.0 -n 1.1.0 -t "A short description" -p 10-20 -j JR-1 JR-2 -w JR-3
+#
+# Placeholders:
+#   {name}                  release name
+#   {now-version}           current version (-c), also available as {current-version}
+#   {next-version}          next version (-n)
+#   {next-version.major}    a version component: major, minor, patch, pre (pre-release) or build
+#   {tweet}                 release short description (-t)
+#   {pvt-line-range}        PVT line range (-p), or the lines between the release pvt-markers
+#   {pvt-file}              file of the release pvt-markers
+#   {main-class}            main class (--main-class), or found with the release main-pattern
+#   {jiras}                 released jiras (-j)
+#   {wip-jiras}             work in progress jiras (-w)
+#   {jira.JR-1.summary}     summary, description, status or fix-versions of a jira of the release tracker
+#   {jira-summaries}        summaries of the released jiras, also the default tweet with a tracker
+#
+# A placeholder may be followed by filters and a default value: {tweet | truncate(280) | "n/a"}
+# A string holding only a placeholder keeps its type, e.g. "{jiras}" is printed as a json array.
+# Literal braces are written as {{ and }}.
+
+# Tables shared by templates through `include`
+[fragments.common]
+region = "GLOBAL"
+tweet = "{tweet | truncate(280)}"
+
+[[releases]]
+name = "example"
+# json schema the printed templates must match, relative to this file (templates may set their own)
+# schema = "example.schema.json"
+# jiras collected by --jiras-from-git, commits with the wip marker add to the wip jiras
+jira-pattern = "[A-Z][A-Z0-9]+-\\d+"
+wip-marker = "WIP"
+# without --current and --next the versions are derived from the latest tag matching the pattern and
+# bumped by: major, minor, patch or conventional (inferred from the commit messages since the tag)
+# tag-pattern = "example-v{version}"
+# bump = "patch"
+# the current version may be read from AWS SSM (with the aws cli) or from a json file of parameter values
+# current-version-source = { type = "ssm", path = "/uat/example/version", region = "eu-west-2" }
+# current-version-source = { type = "file", file = "parameters.json", path = "/uat/example/version" }
+# {pvt-line-range}, {pvt-file} and {main-class} are looked up in the git repository at HEAD
+# pvt-markers = ["// PVT-START", "// PVT-END"]
+# main-pattern = "public static void main"
+# jira metadata from the Jira REST api (bearer token from $JIRA_TOKEN) or from <path>/<key>.json files
+# tracker = { type = "jira", url = "https://jira.example.com", token-env = "JIRA_TOKEN" }
+# tracker = { type = "dir", path = "issues" }
+# --tweet-from git, jira or file lists commit subjects, jira summaries or the lines of the tweet file as {items}
+# tweet-template = "{items | join(\"; \")}"
+# tweet-max-length = 280
+# tweet-file = "release-notes.txt"
+# ask for the values not given as options when run from a terminal, as with --interactive
+# interactive = true
+# parameters override the defaults of the release options, a required one must be given (or asked for) and
+# the defaults of tweet, pvt-line-range and main-class may hold placeholders
+# [[releases.parameters]]
+# name = "tweet"
+# required = true
+# pattern = "^.{1,280}$"
+# help = "Announcement of the release"
+# [[releases.parameters]]
+# name = "pvt-line-range"
+# default = "1-10"
+# variables available as placeholders, e.g. {environment}, overridden by TEMPLAR_VAR_ENVIRONMENT, --vars-file
+# and --var environment=prod
+# [releases.vars]
+# environment = "uat"
+
+[[releases.templates]]
+id = "default"
+include = ["common"]
+[releases.templates.content]
+title = "{name | upper} {now-version} -> {next-version}"
+now-version = "{now-version}"
+next-version = "{next-version}"
+business-verification = [
+    "This release is independent",
+    "PVT: https://github.com/kkarad/templar/blob/master/src/pvt.rs#L{pvt-line-range}",
+]
+# the release jiras and wip jiras are appended to `jiras` and `wip-jiras`, `release-date` defaults to now
+jiras = []
+wip-jiras = []
+release-date = ""
+
+# Templates may extend another template of any release as "<release>/<template id>"
+[[releases.templates]]
+id = "external"
+extends = "example/default"
+# the injected release date (--release-date) is formatted in the template's timezone
+date-format = "%d/%m/%Y %H:%M"
+timezone = "Europe/London"
+[releases.templates.content]
+region = "EXTERNAL"
+summary = "{jiras | length} jiras released, wip: {wip-jiras | join(\", \") | \"none\"}"
+links = [
+    { _each = "{jiras}", _as = "jira", key = "{jira}", url = "https://jira.example.com/browse/{jira}" },
+]
 "#.to_string()
-}
\ No newline at end of file
+}
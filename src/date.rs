@@ -0,0 +1,157 @@
+//Release date expressions, resolved in the timezone of each template:
+//now, today, tomorrow, yesterday, next <weekday>, +<n><m|h|d|w> or <n><m|h|d|w> ago, optionally followed by a time of day
+//(HH:MM[:SS]), or an ISO date, date time or RFC 3339 timestamp
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseDate {
+    base: Base,
+    time: Option<NaiveTime>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Base {
+    Now,
+    Day(i64),
+    Next(Weekday),
+    Offset(Duration),
+    Local(NaiveDateTime),
+    Instant(DateTime<FixedOffset>),
+}
+
+impl Default for ReleaseDate {
+    fn default() -> ReleaseDate {
+        ReleaseDate { base: Base::Now, time: None }
+    }
+}
+
+impl ReleaseDate {
+    pub fn parse(expr: &str) -> Result<ReleaseDate, String> {
+        let expr = expr.trim();
+        let invalid = || format!("Invalid release date '{}', expected now, today, tomorrow, yesterday, \
+            next <weekday>, +<n><m|h|d|w> or <n><m|h|d|w> ago optionally followed by HH:MM, or an ISO date", expr);
+        if let Ok(instant) = DateTime::parse_from_rfc3339(expr) {
+            return Ok(ReleaseDate { base: Base::Instant(instant), time: None });
+        }
+        for format in &["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+            if let Ok(local) = NaiveDateTime::parse_from_str(expr, format) {
+                return Ok(ReleaseDate { base: Base::Local(local), time: None });
+            }
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(expr, "%Y-%m-%d") {
+            return Ok(ReleaseDate { base: Base::Local(date.and_hms(0, 0, 0)), time: None });
+        }
+        let lowercase = expr.to_lowercase();
+        let mut words: Vec<&str> = lowercase.split_whitespace().collect();
+        let time = match words.last().and_then(|word| parse_time(word)) {
+            Some(time) => {
+                words.pop();
+                Some(time)
+            }
+            None => None,
+        };
+        let base = match words.as_slice() {
+            ["now"] => Base::Now,
+            ["today"] => Base::Day(0),
+            [] if time.is_some() => Base::Day(0),
+            ["tomorrow"] => Base::Day(1),
+            ["yesterday"] => Base::Day(-1),
+            ["next", weekday] => Base::Next(weekday.parse().map_err(|_| invalid())?),
+            [offset] if offset.starts_with('+') => Base::Offset(parse_offset(&offset[1..]).ok_or_else(invalid)?),
+            [offset, "ago"] => Base::Offset(-parse_offset(offset).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        Ok(ReleaseDate { base, time })
+    }
+
+    pub fn resolve(&self, now: DateTime<Utc>, timezone: Tz) -> Result<DateTime<Tz>, String> {
+        let now = now.with_timezone(&timezone).naive_local();
+        let midnight = NaiveTime::from_hms(0, 0, 0);
+        let local = match &self.base {
+            Base::Instant(instant) => return Ok(instant.with_timezone(&timezone)),
+            Base::Now => now,
+            Base::Day(days) => (now.date() + Duration::days(*days)).and_time(midnight),
+            Base::Next(weekday) => {
+                let today = now.date().weekday().num_days_from_monday() as i64;
+                let days = (weekday.num_days_from_monday() as i64 - today + 6) % 7 + 1;
+                (now.date() + Duration::days(days)).and_time(midnight)
+            }
+            Base::Offset(offset) => now.checked_add_signed(*offset)
+                .ok_or_else(|| format!("Release date {} days from {} does not exist", offset.num_days(), now.date()))?,
+            Base::Local(local) => *local,
+        };
+        let local = self.time.map_or(local, |time| local.date().and_time(time));
+        timezone.from_local_datetime(&local).earliest()
+            .ok_or_else(|| format!("Release date {} does not exist in timezone {}", local, timezone.name()))
+    }
+}
+
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(word, "%H:%M:%S").or_else(|_| NaiveTime::parse_from_str(word, "%H:%M")).ok()
+}
+
+//<n><unit> where n is unsigned and the unit is m (minutes), h (hours), d (days) or w (weeks), None when out of the
+//duration range
+fn parse_offset(word: &str) -> Option<Duration> {
+    let unit = word.chars().last()?;
+    let digits = word.get(..word.len() - 1).filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))?;
+    let n: i64 = digits.parse().ok()?;
+    let seconds = match unit {
+        'm' => n.checked_mul(60)?,
+        'h' => n.checked_mul(60 * 60)?,
+        'd' => n.checked_mul(24 * 60 * 60)?,
+        'w' => n.checked_mul(7 * 24 * 60 * 60)?,
+        _ => return None,
+    };
+    //durations hold milliseconds
+    seconds.checked_mul(1000)?;
+    Some(Duration::seconds(seconds))
+}
+
+//IANA timezone name of a template, e.g. "Europe/London"
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Timezone(pub Tz);
+
+impl Default for Timezone {
+    fn default() -> Timezone {
+        Timezone(Tz::UTC)
+    }
+}
+
+impl TryFrom<String> for Timezone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Timezone, String> {
+        value.parse().map(Timezone)
+            .map_err(|_| format!("unknown timezone '{}', expected a name such as UTC or Europe/London", value))
+    }
+}
+
+//strftime pattern of the injected release date, e.g. "%d/%m/%Y"
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String")]
+pub struct DateFormat(pub String);
+
+impl Default for DateFormat {
+    fn default() -> DateFormat {
+        DateFormat(DEFAULT_FORMAT.to_string())
+    }
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<DateFormat, String> {
+        if StrftimeItems::new(&value).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("invalid date-format '{}'", value));
+        }
+        Ok(DateFormat(value))
+    }
+}
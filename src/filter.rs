@@ -0,0 +1,178 @@
+use serde_json::Value as Json;
+
+use crate::placeholder::Filter;
+use crate::version::{self, Bump, Version};
+
+type FilterFn = fn(Json, &[Json]) -> Result<Json, String>;
+
+const FILTERS: &[(&str, FilterFn)] = &[
+    ("upper", upper),
+    ("lower", lower),
+    ("trim", trim),
+    ("truncate", truncate),
+    ("join", join),
+    ("first", first),
+    ("last", last),
+    ("semver_bump", semver_bump),
+    ("length", length),
+    ("number", number),
+    ("bool", boolean),
+    ("not", not),
+];
+
+pub fn exists(name: &str) -> bool {
+    FILTERS.iter().any(|(filter, _)| filter.eq(&name))
+}
+
+pub fn unknown(name: &str) -> String {
+    format!("Unknown filter '{}' (available filters: {})", name,
+            FILTERS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", "))
+}
+
+//the name and arguments of the filter, checked before any value is filtered
+pub fn check(filter: &Filter) -> Result<(), String> {
+    if !exists(&filter.name) {
+        return Err(unknown(&filter.name));
+    }
+    match filter.name.as_str() {
+        "truncate" => max_length(&filter.args).map(|_| ()),
+        "join" => separator(&filter.args).map(|_| ()),
+        "semver_bump" => part(&filter.args).map(|_| ()),
+        _ => no_args(&filter.args),
+    }.map_err(|err| format!("Filter '{}': {}", filter.name, err))
+}
+
+pub fn apply(filter: &Filter, value: Json) -> Result<Json, String> {
+    let (_, function) = FILTERS.iter().find(|(name, _)| name.eq(&filter.name))
+        .ok_or_else(|| unknown(&filter.name))?;
+    function(value, &filter.args).map_err(|err| format!("Filter '{}': {}", filter.name, err))
+}
+
+fn upper(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    map_strings(value, &|s| Ok(s.to_uppercase()))
+}
+
+fn lower(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    map_strings(value, &|s| Ok(s.to_lowercase()))
+}
+
+fn trim(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    map_strings(value, &|s| Ok(s.trim().to_string()))
+}
+
+fn truncate(value: Json, args: &[Json]) -> Result<Json, String> {
+    let max = max_length(args)?;
+    map_strings(value, &|s| Ok(s.chars().take(max).collect()))
+}
+
+fn max_length(args: &[Json]) -> Result<usize, String> {
+    match args {
+        [Json::Number(n)] => Ok(n.as_u64().ok_or(format!("Expected a positive length but found {}", n))? as usize),
+        _ => Err("Expected one numeric argument, e.g. truncate(280)".to_string()),
+    }
+}
+
+fn join(value: Json, args: &[Json]) -> Result<Json, String> {
+    let separator = separator(args)?;
+    match value {
+        Json::Array(values) => Ok(Json::String(values.iter()
+            .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+            .collect::<Vec<_>>().join(separator))),
+        other => Ok(other),
+    }
+}
+
+fn separator(args: &[Json]) -> Result<&str, String> {
+    match args {
+        [] => Ok(","),
+        [Json::String(separator)] => Ok(separator.as_str()),
+        _ => Err("Expected one string argument, e.g. join(\", \")".to_string()),
+    }
+}
+
+fn first(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    match value {
+        Json::Array(values) => Ok(values.into_iter().next().unwrap_or(Json::Null)),
+        other => Ok(other),
+    }
+}
+
+fn last(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    match value {
+        Json::Array(values) => Ok(values.into_iter().last().unwrap_or(Json::Null)),
+        other => Ok(other),
+    }
+}
+
+fn semver_bump(value: Json, args: &[Json]) -> Result<Json, String> {
+    let part = part(args)?;
+    map_strings(value, &|version| version::bump(&Version::parse(version)?, part).map(|v| v.to_string()))
+}
+
+fn part(args: &[Json]) -> Result<Bump, String> {
+    match args {
+        [Json::String(part)] => Bump::parse(part),
+        _ => Err("Expected one string argument, e.g. semver_bump(\"minor\")".to_string()),
+    }
+}
+
+fn length(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    match value {
+        Json::Array(values) => Ok(Json::from(values.len())),
+        Json::String(s) => Ok(Json::from(s.chars().count())),
+        Json::Null => Ok(Json::from(0)),
+        other => Err(format!("Unable to take the length of {}", other)),
+    }
+}
+
+fn number(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    match value {
+        Json::String(s) => serde_json::from_str::<serde_json::Number>(s.trim())
+            .map(Json::Number)
+            .map_err(|_| format!("Invalid number '{}'", s)),
+        Json::Bool(b) => Ok(Json::from(b as u8)),
+        Json::Number(n) => Ok(Json::Number(n)),
+        other => Err(format!("Unable to convert {} to a number", other)),
+    }
+}
+
+fn boolean(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    Ok(Json::Bool(truthy(&value)))
+}
+
+fn not(value: Json, args: &[Json]) -> Result<Json, String> {
+    no_args(args)?;
+    Ok(Json::Bool(!truthy(&value)))
+}
+
+pub fn truthy(value: &Json) -> bool {
+    match value {
+        Json::Null => false,
+        Json::Bool(b) => *b,
+        Json::Number(n) => n.as_f64() != Some(0.0),
+        Json::String(s) => !matches!(s.trim().to_lowercase().as_str(), "" | "false" | "no" | "0"),
+        Json::Array(values) => !values.is_empty(),
+        Json::Object(values) => !values.is_empty(),
+    }
+}
+
+fn no_args(args: &[Json]) -> Result<(), String> {
+    if args.is_empty() { Ok(()) } else { Err(format!("Unexpected arguments: {}", Json::Array(args.to_vec()))) }
+}
+
+fn map_strings(value: Json, f: &dyn Fn(&str) -> Result<String, String>) -> Result<Json, String> {
+    match value {
+        Json::String(s) => Ok(Json::String(f(&s)?)),
+        Json::Array(values) => Ok(Json::Array(values.into_iter()
+            .map(|v| map_strings(v, f)).collect::<Result<_, _>>()?)),
+        other => Ok(other),
+    }
+}
@@ -0,0 +1,105 @@
+use serde_json::Value as Json;
+use toml::Value as Toml;
+
+//How rendered templates are serialized, the key order of the templates is kept except for canonical json
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Compact,
+    Pretty,
+    Canonical,
+    Ndjson,
+    Yaml,
+    Toml,
+}
+
+const FORMATS: &[(&str, Format)] = &[
+    ("compact", Format::Compact),
+    ("pretty", Format::Pretty),
+    ("canonical", Format::Canonical),
+    ("ndjson", Format::Ndjson),
+    ("yaml", Format::Yaml),
+    ("toml", Format::Toml),
+];
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Format, String> {
+        FORMATS.iter().find(|(format, _)| format.eq(&name)).map(|(_, format)| *format)
+            .ok_or_else(|| format!("Unknown format '{}' (available formats: {})", name,
+                                   FORMATS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")))
+    }
+
+    //file extension used when every template is written to its own file
+    pub fn extension(&self) -> &str {
+        match self {
+            Format::Compact | Format::Pretty | Format::Canonical | Format::Ndjson => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+
+    //paths of the values the format has no way to write, the nulls of toml
+    pub fn unwritable(&self, json: &Json) -> Vec<String> {
+        match self {
+            Format::Toml => nulls(json, ""),
+            _ => vec![],
+        }
+    }
+
+    pub fn write_all(&self, templates: Vec<Json>) -> Result<String, String> {
+        match self {
+            Format::Ndjson => templates.iter().map(|t| self.write(t)).collect::<Result<Vec<_>, _>>()
+                .map(|lines| lines.join("\n")),
+            Format::Toml => {
+                let mut document = serde_json::Map::new();
+                document.insert("templates".to_string(), Json::Array(templates));
+                self.write(&Json::Object(document))
+            }
+            _ => self.write(&Json::Array(templates)),
+        }
+    }
+
+    pub fn write(&self, json: &Json) -> Result<String, String> {
+        match self {
+            Format::Compact | Format::Ndjson => serde_json::to_string(json).map_err(|err| err.to_string()),
+            Format::Pretty => serde_json::to_string_pretty(json).map_err(|err| err.to_string()),
+            Format::Canonical => serde_json::to_string(&canonical(json)).map_err(|err| err.to_string()),
+            Format::Yaml => serde_yaml::to_string(json)
+                .map(|yaml| yaml.trim_end().to_string())
+                .map_err(|err| err.to_string()),
+            Format::Toml => Toml::try_from(json)
+                .and_then(|toml| toml::to_string(&toml))
+                .map(|toml| toml.trim_end().to_string())
+                .map_err(|err| err.to_string()),
+        }.map_err(|err| format!("Unable to write {:?} output: {}", self, err))
+    }
+}
+
+fn nulls(json: &Json, path: &str) -> Vec<String> {
+    match json {
+        Json::Null => vec![path.to_string()],
+        Json::Object(object) => object.iter().flat_map(|(key, value)| nulls(value, &match path {
+            "" => key.to_owned(),
+            _ => format!("{}.{}", path, key),
+        })).collect(),
+        Json::Array(values) => values.iter().enumerate()
+            .flat_map(|(i, value)| nulls(value, &format!("{}[{}]", path, i))).collect(),
+        _ => vec![],
+    }
+}
+
+//keys are sorted and integral floats are written as integers so that the output is byte identical across runs
+fn canonical(json: &Json) -> Json {
+    match json {
+        Json::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            Json::Object(keys.into_iter().map(|k| (k.to_owned(), canonical(&object[k]))).collect())
+        }
+        Json::Array(values) => Json::Array(values.iter().map(canonical).collect()),
+        Json::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => Json::from(f as i64),
+            _ => Json::Number(n.to_owned()),
+        },
+        other => other.to_owned(),
+    }
+}
@@ -0,0 +1,243 @@
+//Release details read from the git repository of the current directory
+use regex::Regex;
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::process::Command;
+
+use crate::version::Version;
+
+const DEFAULT_JIRA_PATTERN: &str = r"[A-Z][A-Z0-9]+-\d+";
+const DEFAULT_WIP_MARKER: &str = "WIP";
+
+//issue keys mentioned in commit messages, e.g. "[A-Z]+-\d+"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct JiraPattern(Regex);
+
+impl Default for JiraPattern {
+    fn default() -> JiraPattern {
+        JiraPattern(Regex::new(DEFAULT_JIRA_PATTERN).expect("default jira pattern"))
+    }
+}
+
+impl std::fmt::Display for JiraPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.as_str())
+    }
+}
+
+impl JiraPattern {
+    //whether the whole text is an issue key
+    pub fn is_key(&self, text: &str) -> bool {
+        self.0.find(text).is_some_and(|m| m.start() == 0 && m.end() == text.len())
+    }
+
+    //issue keys of the text in order of first mention
+    pub fn find_all(&self, text: &str) -> Vec<String> {
+        let mut keys: Vec<String> = vec![];
+        for key in self.0.find_iter(text).map(|m| m.as_str().to_string()) {
+            if !keys.contains(&key) { keys.push(key); }
+        }
+        keys
+    }
+}
+
+impl TryFrom<String> for JiraPattern {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<JiraPattern, String> {
+        Regex::new(&value).map(JiraPattern).map_err(|err| format!("invalid jira-pattern '{}': {}", value, err))
+    }
+}
+
+//commits whose message holds the marker (as a word) are work in progress, e.g. "WIP"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct WipMarker(Regex);
+
+impl Default for WipMarker {
+    fn default() -> WipMarker {
+        WipMarker::try_from(DEFAULT_WIP_MARKER.to_string()).expect("default wip marker")
+    }
+}
+
+impl TryFrom<String> for WipMarker {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<WipMarker, String> {
+        Regex::new(&format!(r"\b{}\b", regex::escape(&value))).map(WipMarker)
+            .map_err(|err| format!("invalid wip-marker '{}': {}", value, err))
+    }
+}
+
+//release tags with a `{version}` placeholder, e.g. "myapp-v{version}"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct TagPattern(String, Regex);
+
+impl std::fmt::Display for TagPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for TagPattern {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<TagPattern, String> {
+        match value.split_once("{version}") {
+            Some((prefix, suffix)) if !suffix.contains("{version}") => Regex::new(&format!(
+                r"^{}(\d+(?:\.\d+)*(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?){}$", regex::escape(prefix), regex::escape(suffix)))
+                .map(|regex| TagPattern(value.to_owned(), regex)).map_err(|err| format!("invalid tag-pattern '{}': {}", value, err)),
+            _ => Err(format!("invalid tag-pattern '{}', expected one {{version}} placeholder", value)),
+        }
+    }
+}
+
+//(tag, version) of the highest version tagged by the pattern among the tags reachable from HEAD
+pub fn latest_version(pattern: &TagPattern) -> Result<Option<(String, Version)>, String> {
+    let tags = git(&["tag", "--list", "--merged", "HEAD"])?;
+    let mut versions: Vec<(Version, String)> = vec![];
+    for tag in tags.lines().map(str::trim) {
+        if let Some(version) = pattern.1.captures(tag).and_then(|c| c.get(1)) {
+            let version = Version::parse(version.as_str()).map_err(|err| format!("Tag '{}': {}", tag, err))?;
+            versions.push((version, tag.to_string()));
+        }
+    }
+    Ok(versions.into_iter().max_by(|(a, _), (b, _)| a.cmp(b)).map(|(version, tag)| (tag, version)))
+}
+
+//revision range of the commits since the tag, or else since the latest tag, or of the whole history
+pub fn range_since(tag: Option<String>) -> Result<String, String> {
+    let tag = match tag {
+        Some(tag) => Some(tag),
+        None => latest_tag()?,
+    };
+    Ok(tag.map_or("HEAD".to_string(), |tag| format!("{}..HEAD", tag)))
+}
+
+//commit messages of the revision range, oldest first
+pub fn messages(range: &str) -> Result<Vec<String>, String> {
+    let log = git(&["log", "--reverse", "--format=%B%x00", range])?;
+    Ok(log.split('\0').map(str::trim).filter(|m| !m.is_empty()).map(str::to_string).collect())
+}
+
+pub fn latest_tag() -> Result<Option<String>, String> {
+    match git(&["describe", "--tags", "--abbrev=0"]) {
+        Ok(tag) => Ok(Some(tag.trim().to_string())),
+        Err(_) if git(&["rev-parse", "HEAD"]).is_ok() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+//(released, work in progress) issue keys in order of first mention, a key mentioned by any commit that is
+//not work in progress is released
+pub fn jiras(messages: &[String], pattern: &JiraPattern, wip: &WipMarker) -> (Vec<String>, Vec<String>) {
+    let mut released: Vec<String> = vec![];
+    let mut wip_jiras: Vec<String> = vec![];
+    for message in messages {
+        let is_wip = wip.0.is_match(message);
+        for key in pattern.0.find_iter(message).map(|m| m.as_str().to_string()) {
+            if is_wip {
+                if !released.contains(&key) && !wip_jiras.contains(&key) { wip_jiras.push(key); }
+            } else if !released.contains(&key) {
+                wip_jiras.retain(|k| k.ne(&key));
+                released.push(key);
+            }
+        }
+    }
+    (released, wip_jiras)
+}
+
+//start and end markers enclosing the PVT lines, e.g. ["// PVT-START", "// PVT-END"]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "Vec<String>")]
+pub struct PvtMarkers(String, String);
+
+impl TryFrom<Vec<String>> for PvtMarkers {
+    type Error = String;
+
+    fn try_from(value: Vec<String>) -> Result<PvtMarkers, String> {
+        match value.as_slice() {
+            [start, end] if !start.is_empty() && !end.is_empty() && start.ne(end) =>
+                Ok(PvtMarkers(start.to_owned(), end.to_owned())),
+            _ => Err(format!("invalid pvt-markers {:?}, expected two different markers [\"<start>\", \"<end>\"]", value)),
+        }
+    }
+}
+
+//(file, line range) of the lines enclosed by the only pair of PVT markers in the repository at HEAD
+pub fn pvt(markers: &PvtMarkers) -> Result<(String, String), String> {
+    let (start, end) = (find_one(&markers.0)?, find_one(&markers.1)?);
+    if start.0.ne(&end.0) || end.1 <= start.1 + 1 {
+        return Err(format!("Unbalanced PVT markers: '{}' at {}:{} and '{}' at {}:{}",
+                           markers.0, start.0, start.1, markers.1, end.0, end.1));
+    }
+    Ok((start.0, format!("{}-{}", start.1 + 1, end.1 - 1)))
+}
+
+//fully qualified name (package and file name) of the only file with a main entry matching the pattern, an
+//extended regular expression as understood by git grep
+pub fn main_class(pattern: &str) -> Result<String, String> {
+    let files = grep(&["-l", "-E", "-e", pattern])?;
+    let file = match files.as_slice() {
+        [file] => file.to_owned(),
+        [] => return Err(format!("No main entry matching main-pattern '{}' found in the repository at HEAD", pattern)),
+        _ => return Err(format!("Main entry matching main-pattern '{}' found more than once: {}",
+                                pattern, files.join(", "))),
+    };
+    let class = std::path::Path::new(&file).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let source = show(&file)?;
+    let package = Regex::new(r"^\s*package\s+([\w.]+)").expect("package pattern");
+    Ok(match source.lines().find_map(|line| package.captures(line)) {
+        Some(package) => format!("{}.{}", &package[1], class),
+        None => class,
+    })
+}
+
+//(file, line) of the only line holding the text in the repository at HEAD
+fn find_one(text: &str) -> Result<(String, usize), String> {
+    let found: Vec<(String, usize)> = grep(&["-n", "-F", "-e", text])?.iter()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let file = parts.next()?.to_string();
+            parts.next()?.parse().ok().map(|number| (file, number))
+        })
+        .collect();
+    match found.as_slice() {
+        [one] => Ok(one.to_owned()),
+        [] => Err(format!("PVT marker '{}' not found in the repository at HEAD", text)),
+        _ => Err(format!("PVT marker '{}' found more than once: {}", text,
+                         found.iter().map(|(file, line)| format!("{}:{}", file, line)).collect::<Vec<_>>().join(", "))),
+    }
+}
+
+//content of the file at HEAD
+pub fn show(file: &str) -> Result<String, String> {
+    git(&["show", &format!("HEAD:{}", file)])
+}
+
+//matching lines of the files at HEAD without the "HEAD:" prefix, git grep fails with 1 when nothing matches
+fn grep(args: &[&str]) -> Result<Vec<String>, String> {
+    let mut grep = vec!["grep", "-I"];
+    grep.extend_from_slice(args);
+    grep.extend_from_slice(&["HEAD", "--"]);
+    let output = Command::new("git").args(&grep).output()
+        .map_err(|err| format!("Unable to run git: {}", err))?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).lines()
+            .map(|line| line.strip_prefix("HEAD:").unwrap_or(line).to_string())
+            .collect()),
+        Some(1) if output.stderr.is_empty() => Ok(vec![]),
+        _ => Err(format!("git {} failed: {}", grep.join(" "), String::from_utf8_lossy(&output.stderr).trim())),
+    }
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git").args(args).output()
+        .map_err(|err| format!("Unable to run git: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -3,12 +3,34 @@ mod option;
 mod usage;
 mod conf;
 mod release;
+mod placeholder;
+mod filter;
+mod validate;
+mod format;
+mod schema;
+mod date;
+mod git;
+mod version;
+mod check;
+mod tracker;
+mod store;
+mod tweet;
+mod prompt;
+mod parameter;
+mod vars;
 
 use std::env;
-use std::path::PathBuf;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use conf::Conf;
 pub use release::Context;
-use crate::release::{Console, Output};
+use crate::release::{Console, File, Output, Release};
+use crate::format::Format;
+use crate::date::ReleaseDate;
+use crate::version::{Bump, Version};
+use crate::tracker::Issue;
+use crate::prompt::Prompt;
+use crate::parameter::Parameter;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -26,12 +48,16 @@ fn main() {
 fn run() -> Result<(), String> {
     let mut args: Vec<String> = env::args().collect();
     args.remove(0); //remove executable name
-    let commands = vec!["release".to_string()];
+    let commands = vec!["release".to_string(), "config".to_string(), "init".to_string()];
     let (mut general_args, mut command_args) = option::split_args(args, commands);
 
     let home_dir = option::find_long_value(&mut general_args, "--home")?
         .map(PathBuf::from).or_else(dirs::home_dir).ok_or("Unable to locate home directory")?;
-    let conf = conf::init(home_dir)?;
+    let current_dir = env::current_dir().map_err(|err| format!("Unable to locate current directory: {}", err))?;
+    let mut conf_files: Vec<PathBuf> = vec![];
+    while let Some(conf_file) = option::find_long_value(&mut general_args, "--config")? {
+        conf_files.push(PathBuf::from(conf_file));
+    }
 
     if option::find(&mut general_args, "-h", "--help")? {
         println!("{}", usage::main());
@@ -43,38 +69,456 @@ fn run() -> Result<(), String> {
 
     let command = command_args.first().ok_or("No command specified")?.to_owned();
     command_args.remove(0);
+    if command.eq(&"init".to_string()) {
+        return handle_init(&home_dir, &mut command_args.to_vec());
+    }
+    let conf_files = conf::files(&home_dir, &current_dir, &conf_files)?;
+    let conf = conf::init(&conf_files)?;
     if command.eq(&"release".to_string()) {
         handle_release(conf, &mut command_args.to_vec())
+    } else if command.eq(&"config".to_string()) {
+        handle_config(conf, &conf_files, &mut command_args.to_vec())
     } else {
         Err(format!("Unknown command '{}'", command))
     }
 }
 
+fn handle_init(home_dir: &Path, args: &mut Vec<String>) -> Result<(), String> {
+    if option::find(args, "-h", "--help")? {
+        println!("{}", usage::init());
+        return Ok(());
+    }
+    let force = option::find_long(args, "--force")?;
+    let conf_file = args.iter().find(|arg| !arg.starts_with('-')).map(PathBuf::from)
+        .unwrap_or_else(|| conf::user_file(home_dir));
+    conf::create(&conf_file, force)?;
+    println!("Created configuration file: {}", conf_file.display());
+    Ok(())
+}
+
 fn handle_release(conf: Conf, args: &mut Vec<String>) -> Result<(), String> {
     if option::find(args, "-h", "--help")? {
         println!("{}", usage::release());
+        if let Some(name) = args.first() {
+            let release = conf.release(name).ok_or(format!("Unknown release: {}", name))?;
+            print!("{}", usage::parameters(release));
+        }
         return Ok(());
     }
     let release_name = args.first().ok_or("Release name is missing")?.to_owned();
     let release = conf.release(&release_name).ok_or(format!("Unknown release: {}", release_name))?;
-    let current_version = option::find_value(args, "-c", "--current")?;
-    let next_version = option::find_value(args, "-n", "--next")?;
+    let current_version = option::find_value(args, "-c", "--current")?.map(|v| Version::parse(&v)).transpose()?;
+    let next_version = option::find_value(args, "-n", "--next")?.map(|v| Version::parse(&v)).transpose()?;
     let tweet = option::find_value(args, "-t", "--tweet")?;
     let pvt_line_range = option::find_value(args, "-p", "--pvt-line-range")?;
+    let pvt_file = option::find_long_value(args, "--pvt-file")?;
+    let main_class = option::find_long_value(args, "--main-class")?;
     let jiras: Option<Vec<String>> = option::find_values(args, "-j", "--jiras")?;
     let wip_jiras: Option<Vec<String>> = option::find_values(args, "-w", "--wip-jiras")?;
-    let context = Context::new(
+    let jiras_from_git = option::find_long_optional_value(args, "--jiras-from-git")?;
+    let mut vars: Vec<String> = vec![];
+    while let Some(var) = option::find_long_value(args, "--var")? {
+        vars.push(var);
+    }
+    let vars_file = option::find_long_value(args, "--vars-file")?.map(PathBuf::from);
+    let vars = vars::variables(release, &vars, vars_file.as_deref())?;
+    let tweet_from = option::find_long_value(args, "--tweet-from")?;
+    if let Some(source) = &tweet_from {
+        check_tweet_source(release, source)?;
+    }
+    let release_date = option::find_long_value(args, "--release-date")?
+        .map(|date| release.check("release-date", &date).and_then(|_| ReleaseDate::parse(&date))).transpose()?;
+    let output = option::find_value(args, "-o", "--output")?;
+    let split = option::find_long(args, "--split")?;
+    let force = option::find_long(args, "--force")?;
+    let format = option::find_long_value(args, "--format")?
+        .map_or(Ok(Format::Compact), |format| Format::parse(&format))?;
+    let check = option::find_long(args, "--check")?;
+    let strict = option::find_long(args, "--strict")?;
+    let parse = option::find_long(args, "--parse")?;
+    if strict && !check {
+        return Err("The --strict option requires --check".to_string());
+    }
+    if split && output.is_none() {
+        return Err("The --split option requires --output <dir>".to_string());
+    }
+    let interactive = option::find_long(args, "--interactive")? || (release.interactive() && io::stdin().is_terminal());
+    let mut prompt = if interactive { Some(Prompt::new(io::stdin().lock(), io::stderr())) } else { None };
+    let (current_version, next_version) = resolve_versions(release, prompt.as_mut(), current_version, next_version)?;
+    let (jiras, wip_jiras) = resolve_jiras(release, prompt.as_mut(), jiras, wip_jiras, jiras_from_git.as_ref())?;
+    let (pvt_file, pvt_line_range) = resolve_pvt(release, prompt.as_mut(), pvt_line_range, pvt_file)?;
+    let main_class = resolve_main_class(release, prompt.as_mut(), main_class)?;
+    let issues = match &release.tracker {
+        Some(tracker) => tracker::issues(tracker.tracker().as_ref(), release.jira_pattern(),
+                                         &jiras.iter().chain(&wip_jiras).collect::<Vec<_>>())?,
+        None => vec![],
+    };
+    let tweet_items = match (&tweet, tweet_from) {
+        (None, Some(source)) => Some(tweet_items(release, &source, &jiras, &issues, jiras_from_git.clone().flatten())?),
+        _ => None,
+    };
+    let tweet = match tweet_items {
+        Some(_) => String::new(),
+        None => resolve_tweet(release, prompt.as_mut(), tweet, jira_summaries(&issues, &jiras))?,
+    };
+    let release_date = resolve_release_date(release, prompt.as_mut(), release_date)?;
+    let mut context = Context::new(
         release_name,
-        current_version.unwrap_or_else(|| "1".to_string()),
-        next_version.unwrap_or_else(|| "2".to_string()),
-        tweet.unwrap_or_else(|| "default tweet".to_string()),
-        pvt_line_range.unwrap_or_else(|| "10-20".to_string()),
-        jiras.unwrap_or_else(|| vec![]),
-        wip_jiras.unwrap_or_else(|| vec![]),
+        current_version,
+        next_version,
+        tweet,
+        pvt_line_range,
+        pvt_file,
+        main_class,
+        jiras,
+        wip_jiras,
+        release_date,
+        issues,
     );
-    if option::find_long(args, "--parse")? {
+    context.vars = vars;
+    if let Some(items) = tweet_items {
+        context.tweet = tweet::compose(release.tweet_template(), &items, release.tweet_max_length(), &context)?;
+    }
+    parameter::apply(release.parameters(), &mut context)?;
+    if parse {
         println!("{:?}", context);
         return Ok(());
     }
-    Console::new().print(release.templates(), &context)
+    if check {
+        return handle_check(release, &context, jiras_from_git.flatten(), strict);
+    }
+    match output {
+        Some(path) => File::new(PathBuf::from(path), split, force, format).print(release.templates(), &context),
+        None => Console::new(format).print(release.templates(), &context),
+    }
+}
+
+//a tweet source the release is configured for
+fn check_tweet_source(release: &Release, source: &str) -> Result<(), String> {
+    match source {
+        "git" => Ok(()),
+        "jira" if release.tracker.is_none() => Err("The --tweet-from jira option requires a release tracker".to_string()),
+        "file" if release.tweet_file.is_none() => Err("The --tweet-from file option requires a release tweet-file".to_string()),
+        "jira" | "file" => Ok(()),
+        _ => Err(format!("Unknown tweet source '{}' (available sources: git, jira, file)", source)),
+    }
+}
+
+//commit subjects, summaries of the released jiras or lines of the release tweet-file to compose the tweet from,
+//at least one of them, from a checked source
+fn tweet_items(release: &Release, source: &str, jiras: &[String], issues: &[Issue], range: Option<String>)
+               -> Result<Vec<String>, String> {
+    let (items, kind) = match (source, &release.tweet_file) {
+        ("jira", _) => (jiras.iter()
+            .filter_map(|jira| issues.iter().find(|issue| issue.key.eq(jira)))
+            .map(|issue| issue.summary.to_owned())
+            .collect(), "jira summaries"),
+        ("file", Some(file)) => {
+            let content = std::fs::read_to_string(file)
+                .map_err(|err| format!("Unable to read tweet file '{}': {}", file.display(), err))?;
+            (content.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect(),
+             "lines in the tweet file")
+        }
+        _ => (git::messages(&commit_range(release, range)?)?.iter()
+            .filter_map(|message| message.lines().next())
+            .filter(|subject| !subject.starts_with("Merge "))
+            .map(str::to_string)
+            .collect::<Vec<_>>(), "commit subjects"),
+    };
+    match items.is_empty() {
+        true => Err(format!("No {} to compose the tweet from, give the tweet with -t", kind)),
+        false => Ok(items),
+    }
+}
+
+//the configured default of a release value, suggested when asking for it
+fn suggestion<'a>(release: &'a Release, name: &str) -> Option<&'a str> {
+    release.parameter(name).and_then(Parameter::default)
+}
+
+//jiras of a parameter default, separated by spaces or commas
+fn split_jiras(jiras: &str) -> Vec<String> {
+    jiras.split(|c: char| c.is_whitespace() || c == ',').filter(|jira| !jira.is_empty()).map(str::to_string).collect()
+}
+
+//the summaries of the released jiras looked up in the tracker, if any
+fn jira_summaries(issues: &[Issue], jiras: &[String]) -> Option<String> {
+    let summaries: Vec<&str> = issues.iter()
+        .filter(|issue| jiras.contains(&issue.key) && !issue.summary.is_empty())
+        .map(|issue| issue.summary.as_str())
+        .collect();
+    if summaries.is_empty() { None } else { Some(summaries.join("; ")) }
+}
+
+//the default of the parameter of the release, or else the placeholder value with a warning
+fn default(release: &Release, name: &str, placeholder: &str) -> Result<String, String> {
+    if let Some(default) = release.fallback(name)? {
+        return Ok(default.to_string());
+    }
+    let options = parameter::PARAMETERS.iter().find(|(parameter, _)| parameter.eq(&name))
+        .map_or("", |(_, options)| *options);
+    eprintln!("warning: No {} for release '{}', using '{}' (use {} or declare its default in [[releases.parameters]])",
+              name, release.name, placeholder, options);
+    Ok(placeholder.to_string())
+}
+
+//prints the check warnings, which only fail the release when strict
+fn handle_check(release: &Release, context: &Context, range: Option<String>, strict: bool) -> Result<(), String> {
+    let messages = git::messages(&commit_range(release, range)?)?;
+    let warnings = check::check(context, release.jira_pattern(), &messages)?;
+    for warning in &warnings {
+        eprintln!("warning: {}", warning);
+    }
+    match warnings.len() {
+        0 => {
+            let count = context.jiras.len() + context.wip_jiras.len();
+            println!("ok: {} jiras match the PVT code and the commit messages", count);
+            Ok(())
+        }
+        count if strict => Err(format!("{} check warning(s) with --strict", count)),
+        _ => Ok(()),
+    }
+}
+
+//Each release value is resolved from its option, or else the answer when interactive (suggesting the value derived
+//from the release or the parameter default), or else the value derived from the release, or else the parameter
+//default, the versions resolved here must increase
+fn resolve_versions(release: &Release, prompt: Option<&mut Prompt<impl BufRead, impl Write>>, current: Option<Version>,
+                    next: Option<Version>) -> Result<(Version, Version), String> {
+    let (current, next) = match prompt {
+        Some(prompt) => ask_versions(prompt, release, current, next)?,
+        None => versions(release, current, next)?,
+    };
+    if next <= current {
+        return Err(format!("Next version '{}' must be greater than current version '{}'", next, current));
+    }
+    Ok((current, next))
+}
+
+//the given jiras and wip jiras with the ones mentioned by the commits of the revision range, or else asked for, or
+//else the parameter defaults
+fn resolve_jiras(release: &Release, mut prompt: Option<&mut Prompt<impl BufRead, impl Write>>,
+                 jiras: Option<Vec<String>>, wip_jiras: Option<Vec<String>>, from_git: Option<&Option<String>>)
+                 -> Result<(Vec<String>, Vec<String>), String> {
+    let ask = jiras.is_none() && wip_jiras.is_none() && from_git.is_none();
+    let fallback = |name: &str| match (prompt.is_none(), from_git) {
+        (true, None) => Ok::<_, String>(release.fallback(name)?.map(split_jiras).unwrap_or_default()),
+        _ => Ok(vec![]),
+    };
+    let mut jiras = match jiras {
+        Some(jiras) => jiras,
+        None => fallback("jiras")?,
+    };
+    let mut wip_jiras = match wip_jiras {
+        Some(wip_jiras) => wip_jiras,
+        None => fallback("wip-jiras")?,
+    };
+    if let Some(range) = from_git {
+        let messages = git::messages(&commit_range(release, range.to_owned())?)?;
+        let (released, wip) = git::jiras(&messages, release.jira_pattern(), release.wip_marker());
+        jiras.extend(released.into_iter().filter(|jira| !jiras.contains(jira)).collect::<Vec<_>>());
+        wip_jiras.extend(wip.into_iter().filter(|jira| !wip_jiras.contains(jira)).collect::<Vec<_>>());
+    }
+    if let (Some(prompt), true) = (&mut prompt, ask) {
+        let check = |jira: &str| match release.jira_pattern().is_key(jira) {
+            true => Ok(()),
+            false => Err(format!("Invalid jira '{}', expected a key matching jira-pattern '{}'", jira, release.jira_pattern())),
+        };
+        for (name, label, values) in [("jiras", "Jiras", &mut jiras), ("wip-jiras", "Work in progress jiras", &mut wip_jiras)] {
+            let suggestion = suggestion(release, name).map(split_jiras).unwrap_or_default();
+            *values = prompt.ask_many(label, &suggestion, |jira| check(jira).and_then(|_| release.check(name, jira)))?;
+            if values.is_empty() && release.parameter(name).is_some_and(Parameter::required) {
+                return Err(format!("No value given for the {}", label.to_lowercase()));
+            }
+        }
+    }
+    Ok((jiras, wip_jiras))
+}
+
+//the PVT file and line range, the file located by the markers is kept with a given range unless the file is given
+//as well
+fn resolve_pvt(release: &Release, prompt: Option<&mut Prompt<impl BufRead, impl Write>>, range: Option<String>,
+               file: Option<String>) -> Result<(String, String), String> {
+    let (located_file, range) = match (range, release.pvt_markers(), prompt) {
+        (Some(range), None, _) => (String::new(), range),
+        (Some(range), Some(_), _) if file.is_some() => (String::new(), range),
+        (Some(range), Some(markers), _) => (git::pvt(markers)?.0, range),
+        (None, markers, Some(prompt)) => {
+            let found = markers.map(git::pvt).transpose()?;
+            let suggestion = found.as_ref().map(|(_, range)| range.as_str()).or(suggestion(release, "pvt-line-range"));
+            let range = prompt.ask("PVT line range", suggestion, |range| match range.contains('{') {
+                true => Ok(range.to_string()),
+                false => check::line_range(range).and_then(|_| release.check("pvt-line-range", range)).map(|_| range.to_string()),
+            })?;
+            (found.map(|(file, _)| file).unwrap_or_default(), range)
+        }
+        (None, Some(markers), None) => git::pvt(markers)?,
+        (None, None, None) => (String::new(), default(release, "pvt-line-range", "10-20")?),
+    };
+    Ok((file.unwrap_or(located_file), range))
+}
+
+fn resolve_main_class(release: &Release, prompt: Option<&mut Prompt<impl BufRead, impl Write>>, class: Option<String>)
+                      -> Result<String, String> {
+    match (class, release.main_pattern(), prompt) {
+        (Some(class), _, _) => Ok(class),
+        (None, pattern, Some(prompt)) => {
+            let found = pattern.map(git::main_class).transpose()?;
+            let suggestion = found.as_deref().or(suggestion(release, "main-class")).unwrap_or_default();
+            prompt.ask("Main class", Some(suggestion), |class| release.check("main-class", class).map(|_| class.to_string()))
+        }
+        (None, Some(pattern), None) => git::main_class(pattern),
+        (None, None, None) => Ok(release.fallback("main-class")?.unwrap_or_default().to_string()),
+    }
+}
+
+//the given tweet, or else the summaries of the released jiras in the tracker
+fn resolve_tweet(release: &Release, prompt: Option<&mut Prompt<impl BufRead, impl Write>>, tweet: Option<String>,
+                 summaries: Option<String>) -> Result<String, String> {
+    match (tweet, prompt) {
+        (Some(tweet), _) => Ok(tweet),
+        (None, Some(prompt)) => prompt.ask("Tweet", summaries.as_deref().or(suggestion(release, "tweet")),
+                                           |tweet| release.check("tweet", tweet).map(|_| tweet.to_string())),
+        (None, None) => match summaries {
+            Some(summaries) => Ok(summaries),
+            None => default(release, "tweet", "default tweet"),
+        },
+    }
+}
+
+fn resolve_release_date(release: &Release, prompt: Option<&mut Prompt<impl BufRead, impl Write>>,
+                        date: Option<ReleaseDate>) -> Result<ReleaseDate, String> {
+    match (date, prompt) {
+        (Some(date), _) => Ok(date),
+        (None, Some(prompt)) => prompt.ask("Release date", Some(suggestion(release, "release-date").unwrap_or("now")),
+                                           |date| release.check("release-date", date).and_then(|_| ReleaseDate::parse(date))),
+        (None, None) => release.fallback("release-date")?.map_or(Ok(ReleaseDate::default()), ReleaseDate::parse),
+    }
+}
+
+//versions given as options, or else asked for with the versions derived from the release as suggestions, if they
+//can be derived
+fn ask_versions(prompt: &mut Prompt<impl BufRead, impl Write>, release: &Release, current: Option<Version>,
+                next: Option<Version>) -> Result<(Version, Version), String> {
+    let derived = match release.tag_pattern().is_some() || release.current_version_source.is_some() || current.is_some() {
+        true => versions(release, current.clone(), next.clone()).ok(),
+        false => None,
+    };
+    let current = match current {
+        Some(current) => current,
+        None => prompt.ask("Current version", derived.as_ref().map(|(current, _)| current.to_string()).as_deref()
+            .or(suggestion(release, "current-version")),
+                           |current| release.check("current-version", current).and_then(|_| Version::parse(current)))?,
+    };
+    let next = match (next, derived) {
+        (Some(next), _) => next,
+        (None, derived) => {
+            let suggestion = match (derived, suggestion(release, "next-version")) {
+                (Some((derived, next)), _) if derived.eq(&current) => Some(next.to_string()),
+                (_, Some(next)) => Some(next.to_string()),
+                _ => version::bump(&current, release.bump()).ok().map(|next| next.to_string()),
+            };
+            prompt.ask("Next version", suggestion.as_deref(), |next| match release.check("next-version", next)
+                .and_then(|_| Version::parse(next))? {
+                next if next > current => Ok(next),
+                next => Err(format!("Next version '{}' must be greater than current version '{}'", next, current)),
+            })?
+        }
+    };
+    Ok((current, next))
+}
+
+//versions given as options, or else the current version of the release source or the latest version tagged
+//with the release tag-pattern, the next version defaults to the current one bumped as configured by the release
+fn versions(release: &Release, current: Option<Version>, next: Option<Version>) -> Result<(Version, Version), String> {
+    if let (Some(current), Some(next)) = (&current, &next) {
+        return Ok((current.to_owned(), next.to_owned()));
+    }
+    let current = match (current, &release.current_version_source) {
+        (None, Some(source)) => Some(source.version()?),
+        (current, _) => current,
+    };
+    //the latest tag is only looked up for the current version or the commits a conventional bump is inferred from
+    let conventional = next.is_none() && matches!(release.bump(), Bump::Conventional);
+    let latest = match (release.tag_pattern(), &current) {
+        (Some(pattern), None) => git::latest_version(pattern)?,
+        (Some(pattern), Some(_)) if conventional => git::latest_version(pattern)?,
+        (_, Some(_)) => None,
+        (None, None) => {
+            let declared = release.fallback("current-version")?.is_some();
+            let current = Version::parse(&default(release, "current-version", "1")?)?;
+            let next = match (next, release.fallback("next-version")?) {
+                (Some(next), _) => next,
+                (None, Some(next)) => Version::parse(next)?,
+                (None, None) if declared => version::bump(&current, release.bump())?,
+                (None, None) => Version::parse(&default(release, "next-version", "2")?)?,
+            };
+            return Ok((current, next));
+        }
+    };
+    let current = match current.or_else(|| latest.as_ref().map(|(_, version)| version.to_owned())) {
+        Some(current) => current,
+        None => return Err(format!("No tag matching tag-pattern '{}' found, use --current <version>",
+                                   release.tag_pattern().map(ToString::to_string).unwrap_or_default())),
+    };
+    let next = match next {
+        Some(next) => next,
+        None => {
+            let bump = match release.bump() {
+                Bump::Conventional => {
+                    let range = git::range_since(latest.map(|(tag, _)| tag))?;
+                    Bump::Conventional.infer(&git::messages(&range)?)
+                }
+                bump => bump,
+            };
+            version::bump(&current, bump)?
+        }
+    };
+    Ok((current, next))
+}
+
+//the revision range, or else the commits since the latest tag of the release
+fn commit_range(release: &Release, range: Option<String>) -> Result<String, String> {
+    match range {
+        Some(range) => Ok(range),
+        None => git::range_since(latest_tag(release)?),
+    }
+}
+
+//latest tag with the release tag-pattern, if any
+fn latest_tag(release: &Release) -> Result<Option<String>, String> {
+    Ok(release.tag_pattern().map(git::latest_version).transpose()?.flatten().map(|(tag, _)| tag))
+}
+
+fn handle_config(conf: Conf, conf_files: &[PathBuf], args: &mut Vec<String>) -> Result<(), String> {
+    if option::find(args, "-h", "--help")? {
+        println!("{}", usage::config());
+        return Ok(());
+    }
+    let subcommand = args.first().ok_or("Config command is missing")?.to_owned();
+    args.remove(0);
+    if subcommand.eq("show") {
+        let origin = option::find_long(args, "--origin")?;
+        if origin {
+            for file in conf_files {
+                println!("# {}", file.display());
+            }
+        }
+        for release in conf.releases() {
+            let templates: Vec<&str> = release.templates().iter().map(|t| t.id()).collect();
+            if origin {
+                println!("{}: {} ({})", release.name, templates.join(", "), release.origin.display());
+            } else {
+                println!("{}: {}", release.name, templates.join(", "));
+            }
+        }
+        Ok(())
+    } else if subcommand.eq("validate") {
+        for file in conf_files {
+            println!("ok: {}", file.display());
+        }
+        Ok(())
+    } else {
+        Err(format!("Unknown config command '{}'", subcommand))
+    }
 }
@@ -1,4 +1,4 @@
-use crate::option::OptionType::{List, Value, Flag};
+use crate::option::OptionType::{List, Value, OptionalValue, Flag};
 
 pub fn split_args(args: Vec<String>, commands: Vec<String>) -> (Vec<String>, Vec<String>) {
     if let Some(index) = args.iter().position(|item| commands.contains(item)) {
@@ -13,6 +13,7 @@ pub fn split_args(args: Vec<String>, commands: Vec<String>) -> (Vec<String>, Vec
 enum OptionType {
     Flag,
     Value,
+    OptionalValue,
     List,
 }
 
@@ -49,6 +50,11 @@ pub fn find_value(args: &mut Vec<String>, short: &str, long: &str) -> Result<Opt
     Ok(None)
 }
 
+//an option whose value may be omitted: None when absent, Some(None) when given without a value
+pub fn find_long_optional_value(args: &mut Vec<String>, long: &str) -> Result<Option<Option<String>>, String> {
+    Ok(find_option(args, "", long, OptionalValue)?.map(|values| values.first().cloned()))
+}
+
 pub fn find_values(args: &mut Vec<String>, short: &str, long: &str) -> Result<Option<Vec<String>>, String> {
     find_option(args, short, long, List)
 }
@@ -66,10 +72,10 @@ fn find_option(args: &mut Vec<String>, short: &str, long: &str, op_type: OptionT
             if value.starts_with('-') { break; }
             values.push(value.to_owned());
             value_indices.push(index);
-            if op_type == Value { break; };
+            if op_type == Value || op_type == OptionalValue { break; };
             index += 1;
         }
-        if values.is_empty() {
+        if values.is_empty() && op_type != OptionalValue {
             return Err(format!("Missing option value(s) for: {}", option));
         }
         let original_len = args.len();
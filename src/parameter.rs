@@ -0,0 +1,129 @@
+//Release values declared as parameters of a release: whether they are required, their default (a version, a
+//date expression, or text that may hold placeholders), a pattern the value must match and the help shown by
+//`templar release <name> --help`
+use regex::Regex;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+use crate::date::ReleaseDate;
+use crate::placeholder;
+use crate::release::{Context, Template};
+use crate::version::Version;
+
+//(name, options) of the release values that may be declared as parameters
+pub const PARAMETERS: &[(&str, &str)] = &[
+    ("current-version", "-c, --current"),
+    ("next-version", "-n, --next"),
+    ("tweet", "-t, --tweet"),
+    ("pvt-line-range", "-p, --pvt-line-range"),
+    ("main-class", "--main-class"),
+    ("jiras", "-j, --jiras"),
+    ("wip-jiras", "-w, --wip-jiras"),
+    ("release-date", "--release-date"),
+];
+
+//text parameters whose default may hold placeholders of the other release values
+pub const TEXTS: &[&str] = &["tweet", "pvt-line-range", "main-class"];
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "RawParameter")]
+pub struct Parameter {
+    name: String,
+    required: bool,
+    default: Option<String>,
+    pattern: Option<Regex>,
+    help: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawParameter {
+    name: String,
+    #[serde(default)]
+    required: bool,
+    default: Option<String>,
+    pattern: Option<String>,
+    help: Option<String>,
+}
+
+impl TryFrom<RawParameter> for Parameter {
+    type Error = String;
+
+    fn try_from(raw: RawParameter) -> Result<Parameter, String> {
+        let name = raw.name;
+        if !PARAMETERS.iter().any(|(parameter, _)| parameter.eq(&name)) {
+            return Err(format!("unknown parameter '{}' (available parameters: {})", name,
+                               PARAMETERS.iter().map(|(parameter, _)| *parameter).collect::<Vec<_>>().join(", ")));
+        }
+        if let Some(default) = &raw.default {
+            match name.as_str() {
+                "current-version" | "next-version" => Version::parse(default).map(|_| ()),
+                "release-date" => ReleaseDate::parse(default).map(|_| ()),
+                _ => placeholder::parse(default).map(|_| ()),
+            }.map_err(|err| format!("invalid default of parameter '{}': {}", name, err))?;
+        }
+        let pattern = raw.pattern.map(|pattern| Regex::new(&pattern)
+            .map_err(|err| format!("invalid pattern of parameter '{}': {}", name, err))).transpose()?;
+        Ok(Parameter { name, required: raw.required, default: raw.default, pattern, help: raw.help })
+    }
+}
+
+impl Parameter {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    pub fn options(&self) -> &str {
+        PARAMETERS.iter().find(|(name, _)| name.eq(&self.name)).map_or("", |(_, options)| options)
+    }
+
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_ref().map(Regex::as_str)
+    }
+
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match &self.pattern {
+            Some(pattern) if !pattern.is_match(value) => Err(format!("Parameter '{}': '{}' does not match pattern '{}'",
+                                                                     self.name, value, pattern.as_str())),
+            _ => Ok(()),
+        }
+    }
+}
+
+//renders the text defaults holding placeholders, then checks the release values against the parameter patterns
+pub fn apply(parameters: &[Parameter], ctx: &mut Context) -> Result<(), String> {
+    for parameter in parameters.iter().filter(|p| TEXTS.contains(&p.name.as_str())) {
+        if let Some(default) = parameter.default().filter(|default| default.contains('{')) {
+            let value = ctx.lookup(&parameter.name).and_then(|value| value.as_str().map(str::to_string));
+            if value.as_deref() == Some(default) {
+                let rendered = Template::render_text(default, ctx, None)
+                    .map_err(|err| format!("Parameter '{}': {}", parameter.name, err))?;
+                match parameter.name.as_str() {
+                    "tweet" => ctx.tweet = rendered,
+                    "pvt-line-range" => ctx.pvt_line_range = rendered,
+                    _ => ctx.main_class = rendered,
+                }
+            }
+        }
+    }
+    for parameter in parameters.iter().filter(|p| p.pattern.is_some() && p.name.ne("release-date")) {
+        match ctx.lookup(&parameter.name) {
+            Some(serde_json::Value::Array(values)) =>
+                values.iter().filter_map(|value| value.as_str()).try_for_each(|value| parameter.check(value))?,
+            Some(value) => parameter.check(value.as_str().unwrap_or_default())?,
+            None => {}
+        }
+    }
+    Ok(())
+}
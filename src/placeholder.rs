@@ -0,0 +1,195 @@
+//Placeholder syntax: `{name | filter(arg, ...) | "default"}`, literal braces are escaped as `{{` and `}}`
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use serde_json::Value as Json;
+
+#[derive(Debug, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Placeholder(Expr),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Expr {
+    pub name: String,
+    pub pipes: Vec<Pipe>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Pipe {
+    Default(String),
+    Filter(Filter),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Filter {
+    pub name: String,
+    pub args: Vec<Json>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(Json),
+    Pipe,
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "'{}'", ident),
+            Token::Str(value) => write!(f, "\"{}\"", value),
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Pipe => write!(f, "'|'"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Segment>, String> {
+    let mut segments: Vec<Segment> = vec![];
+    let mut literal = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut literal)));
+                }
+                let tokens = tokenize(&mut chars)?;
+                segments.push(Segment::Placeholder(parse_expr(tokens)?));
+            }
+            '}' => return Err("Unmatched '}' (use '}}' for a literal brace)".to_string()),
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Text(literal));
+    }
+    Ok(segments)
+}
+
+fn tokenize(chars: &mut Peekable<Chars>) -> Result<Vec<Token>, String> {
+    let mut tokens: Vec<Token> = vec![];
+    loop {
+        match chars.next() {
+            None => return Err("Unclosed placeholder, missing '}'".to_string()),
+            Some('}') => return Ok(tokens),
+            Some('|') => tokens.push(Token::Pipe),
+            Some('(') => tokens.push(Token::LParen),
+            Some(')') => tokens.push(Token::RParen),
+            Some(',') => tokens.push(Token::Comma),
+            Some('"') => tokens.push(Token::Str(string_literal(chars)?)),
+            Some(c) if c.is_whitespace() => {}
+            Some(c) if c.is_ascii_digit() || c == '-' && chars.peek().is_some_and(char::is_ascii_digit) => {
+                tokens.push(Token::Number(number_literal(c, chars)?))
+            }
+            Some(c) if is_ident_char(c) => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !is_ident_char(c) { break; }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            Some(c) => return Err(format!("Unexpected character '{}' in placeholder", c)),
+        }
+    }
+}
+
+fn string_literal(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            None => return Err("Unterminated string literal in placeholder".to_string()),
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some(c) => value.push(c),
+                None => return Err("Unterminated string literal in placeholder".to_string()),
+            },
+            Some(c) => value.push(c),
+        }
+    }
+}
+
+fn number_literal(first: char, chars: &mut Peekable<Chars>) -> Result<Json, String> {
+    let mut number = first.to_string();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() && c != '.' { break; }
+        number.push(c);
+        chars.next();
+    }
+    serde_json::from_str::<serde_json::Number>(&number)
+        .map(Json::Number)
+        .map_err(|_| format!("Invalid number '{}' in placeholder", number))
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || c == '.'
+}
+
+fn parse_expr(tokens: Vec<Token>) -> Result<Expr, String> {
+    let mut tokens = tokens.into_iter().peekable();
+    let name = match tokens.next() {
+        Some(Token::Ident(name)) => name,
+        Some(token) => return Err(format!("Expected placeholder name but found {}", token)),
+        None => return Err("Empty placeholder '{}' (use '{{}}' for literal braces)".to_string()),
+    };
+    let mut pipes: Vec<Pipe> = vec![];
+    while let Some(token) = tokens.next() {
+        if token != Token::Pipe {
+            return Err(format!("Unexpected {} in placeholder '{}', expected '|'", token, name));
+        }
+        match tokens.next() {
+            Some(Token::Str(default)) => pipes.push(Pipe::Default(default)),
+            Some(Token::Ident(filter)) => {
+                let args = if tokens.peek() == Some(&Token::LParen) {
+                    tokens.next();
+                    parse_args(&mut tokens, &filter)?
+                } else {
+                    vec![]
+                };
+                pipes.push(Pipe::Filter(Filter { name: filter, args }));
+            }
+            Some(token) => return Err(format!("Expected filter or default value after '|' but found {}", token)),
+            None => return Err("Missing filter or default value after '|'".to_string()),
+        }
+    }
+    Ok(Expr { name, pipes })
+}
+
+fn parse_args(tokens: &mut impl Iterator<Item=Token>, filter: &str) -> Result<Vec<Json>, String> {
+    let mut args: Vec<Json> = vec![];
+    loop {
+        match tokens.next() {
+            Some(Token::RParen) if args.is_empty() => return Ok(args),
+            Some(Token::Str(value)) => args.push(Json::String(value)),
+            Some(Token::Number(value)) => args.push(value),
+            Some(token) => return Err(format!("Expected argument for filter '{}' but found {}", filter, token)),
+            None => return Err(format!("Unclosed argument list for filter '{}'", filter)),
+        }
+        match tokens.next() {
+            Some(Token::Comma) => {}
+            Some(Token::RParen) => return Ok(args),
+            Some(token) => return Err(format!("Expected ',' or ')' in filter '{}' but found {}", filter, token)),
+            None => return Err(format!("Unclosed argument list for filter '{}'", filter)),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//Prompts for the release values not given as options, written to stderr so that the release output is left
+//alone, an empty answer takes the suggestion shown in brackets
+use std::io::{BufRead, Write};
+
+pub struct Prompt<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    pub fn new(input: R, output: W) -> Prompt<R, W> {
+        Prompt { input, output }
+    }
+
+    //asks until the answer is valid, a value without suggestion is required and fails at the end of the input
+    pub fn ask<T>(&mut self, label: &str, suggestion: Option<&str>, parse: impl Fn(&str) -> Result<T, String>)
+                  -> Result<T, String> {
+        loop {
+            match suggestion {
+                Some(suggestion) if !suggestion.is_empty() => self.write(&format!("{} [{}]: ", label, suggestion))?,
+                _ => self.write(&format!("{}: ", label))?,
+            }
+            let line = self.read_line()?;
+            let answer = match (line.as_deref().map(str::trim), suggestion) {
+                (Some(answer), _) if !answer.is_empty() => answer.to_string(),
+                (_, Some(suggestion)) => suggestion.to_string(),
+                (None, None) => return Err(format!("No value given for the {}", label.to_lowercase())),
+                (Some(_), None) => continue,
+            };
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(err) if line.is_none() => return Err(err),
+                Err(err) => self.write(&format!("  {}\n", err))?,
+            }
+        }
+    }
+
+    //values separated by spaces or commas over any number of lines up to an empty one, each checked on entry, an
+    //empty first line takes the suggested values
+    pub fn ask_many(&mut self, label: &str, suggestion: &[String], check: impl Fn(&str) -> Result<(), String>)
+                    -> Result<Vec<String>, String> {
+        match suggestion {
+            [] => self.write(&format!("{} (separated by spaces, an empty line to finish):\n", label))?,
+            _ => self.write(&format!("{} (separated by spaces, an empty line to finish) [{}]:\n", label, suggestion.join(" ")))?,
+        }
+        let mut values: Vec<String> = vec![];
+        loop {
+            self.write("> ")?;
+            let line = match self.read_line()? {
+                Some(line) if !line.trim().is_empty() => line,
+                _ if values.is_empty() => return Ok(suggestion.to_vec()),
+                _ => return Ok(values),
+            };
+            for value in line.split(|c: char| c.is_whitespace() || c == ',').filter(|value| !value.is_empty()) {
+                match check(value) {
+                    Ok(()) if values.iter().any(|v| v.eq(value)) => {}
+                    Ok(()) => values.push(value.to_string()),
+                    Err(err) => self.write(&format!("  {}\n", err))?,
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str) -> Result<(), String> {
+        self.output.write_all(text.as_bytes()).and_then(|_| self.output.flush())
+            .map_err(|err| format!("Unable to write prompt: {}", err))
+    }
+
+    //the next line of the input, None at its end
+    fn read_line(&mut self) -> Result<Option<String>, String> {
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) => {
+                self.write("\n")?;
+                Ok(None)
+            }
+            Ok(_) => Ok(Some(line)),
+            Err(err) => Err(format!("Unable to read answer: {}", err)),
+        }
+    }
+}
@@ -1,99 +1,508 @@
 extern crate chrono;
 
 use serde::Deserialize;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
 use serde_json::Value as Json;
 use toml::Value as Toml;
 use chrono::Utc;
 
+use crate::date::{DateFormat, ReleaseDate, Timezone};
+use crate::filter;
+use crate::git::{JiraPattern, PvtMarkers, TagPattern, WipMarker};
+use crate::format::Format;
+use crate::parameter::Parameter;
+use crate::schema;
+use crate::placeholder::{self, Expr, Pipe, Segment};
+use crate::store::VersionSource;
+use crate::tracker::{Issue, TrackerConf, ISSUE_FIELDS};
+use crate::version::{Bump, Version};
+
+const INJECTED_KEYS: &[&str] = &["jiras", "wip-jiras", "release-date"];
+
+//the tweet composed by --tweet-from renders the `{items}` (commit subjects, jira summaries or file lines)
+const DEFAULT_TWEET_TEMPLATE: &str = "{items | join(\"; \")}";
+
+const DEFAULT_TWEET_MAX_LENGTH: usize = 280;
+
+const PLACEHOLDERS: &[&str] = &[
+    "name", "now-version", "current-version", "next-version", "tweet", "pvt-line-range", "pvt-file", "main-class",
+    "jiras", "wip-jiras", "jira-summaries",
+];
+
+//placeholders of the versions whose components are available as e.g. {next-version.major}
+const VERSIONS: &[&str] = &["now-version", "current-version", "next-version"];
+
+const VERSION_COMPONENTS: &[&str] = &["major", "minor", "patch", "pre", "build"];
+
 #[derive(Debug)]
+#[allow(clippy::manual_non_exhaustive)]
 pub struct Context {
     pub name: String,
-    pub current_version: String,
-    pub next_version: String,
+    pub current_version: Version,
+    pub next_version: Version,
     pub tweet: String,
     pub pvt_line_range: String,
+    pub pvt_file: String,
+    pub main_class: String,
     pub jiras: Vec<String>,
     pub wip_jiras: Vec<String>,
+    pub release_date: ReleaseDate,
+    pub issues: Vec<Issue>,
+    pub vars: serde_json::Map<String, Json>,
     _secret: (),
 }
 
 impl Context {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(name: String,
-               current_version: String,
-               next_version: String,
+               current_version: Version,
+               next_version: Version,
                tweet: String,
                pvt_line_range: String,
+               pvt_file: String,
+               main_class: String,
                jiras: Vec<String>,
-               wip_jiras: Vec<String>) -> Context {
+               wip_jiras: Vec<String>,
+               release_date: ReleaseDate,
+               issues: Vec<Issue>) -> Context {
         Context {
             name,
             current_version,
             next_version,
             tweet,
             pvt_line_range,
+            pvt_file,
+            main_class,
             jiras,
             wip_jiras,
+            release_date,
+            issues,
+            vars: serde_json::Map::new(),
             _secret: (),
         }
     }
+
+    pub fn issue(&self, key: &str) -> Option<&Issue> {
+        self.issues.iter().find(|issue| issue.key.eq(key))
+    }
+
+    pub fn is_placeholder(name: &str) -> bool {
+        match name.split_once('.') {
+            Some(("jira", issue)) => issue.rsplit_once('.').is_some_and(|(_, field)| ISSUE_FIELDS.contains(&field)),
+            Some((version, component)) => VERSIONS.contains(&version) && VERSION_COMPONENTS.contains(&component),
+            None => PLACEHOLDERS.contains(&name),
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<Json> {
+        let string = |value: &String| Some(Json::String(value.to_owned()));
+        let list = |values: &Vec<String>| Some(values.iter().map(|v| Json::String(v.to_owned())).collect());
+        match name {
+            "name" => string(&self.name),
+            "now-version" | "current-version" => string(&self.current_version.to_string()),
+            "next-version" => string(&self.next_version.to_string()),
+            "tweet" => string(&self.tweet),
+            "pvt-line-range" => string(&self.pvt_line_range),
+            "pvt-file" => string(&self.pvt_file),
+            "main-class" => string(&self.main_class),
+            "jiras" => list(&self.jiras),
+            "wip-jiras" => list(&self.wip_jiras),
+            "jira-summaries" => list(&self.jiras.iter()
+                .filter_map(|jira| self.issue(jira))
+                .map(|issue| issue.summary.to_owned())
+                .collect()),
+            _ => match name.split_once('.') {
+                Some(("jira", issue)) => issue.rsplit_once('.')
+                    .and_then(|(key, field)| self.issue(key)?.field(field)),
+                Some(("now-version", component)) | Some(("current-version", component)) =>
+                    self.current_version.component(component),
+                Some(("next-version", component)) => self.next_version.component(component),
+                _ => self.vars.get(name).cloned(),
+            },
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct Template {
     id: String,
+    extends: Option<String>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default, rename = "array-merge")]
+    array_merge: ArrayMerge,
+    #[serde(default, rename = "inject-at")]
+    inject_at: InjectAt,
+    schema: Option<PathBuf>,
+    #[serde(default, rename = "date-format")]
+    date_format: DateFormat,
+    #[serde(default)]
+    timezone: Timezone,
+    #[serde(default = "empty_content")]
     content: Toml,
 }
 
+//Where the keys missing from the content (jiras, wip-jiras and release-date) are injected:
+//"start", "end", "before:<key>" or "after:<key>"
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum InjectAt {
+    Start,
+    #[default]
+    End,
+    Before(String),
+    After(String),
+}
+
+impl TryFrom<String> for InjectAt {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<InjectAt, String> {
+        match value.split_once(':') {
+            None if value.eq("start") => Ok(InjectAt::Start),
+            None if value.eq("end") => Ok(InjectAt::End),
+            Some(("before", key)) if !key.is_empty() => Ok(InjectAt::Before(key.to_string())),
+            Some(("after", key)) if !key.is_empty() => Ok(InjectAt::After(key.to_string())),
+            _ => Err(format!("invalid inject-at '{}', expected start, end, before:<key> or after:<key>", value)),
+        }
+    }
+}
+
+//How arrays of an extended template or included fragment are merged with the template's own arrays
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayMerge {
+    #[default]
+    Replace,
+    Append,
+}
+
+fn empty_content() -> Toml {
+    Toml::Table(toml::value::Table::new())
+}
+
+//Context of the values visible to placeholders, extended by the items bound in `_each` loops
+struct Scope<'a> {
+    ctx: &'a Context,
+    parent: Option<&'a Scope<'a>>,
+    binding: Option<(String, Json)>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(ctx: &'a Context) -> Scope<'a> {
+        Scope { ctx, parent: None, binding: None }
+    }
+
+    fn bind(&'a self, name: &str, value: Json) -> Scope<'a> {
+        Scope { ctx: self.ctx, parent: Some(self), binding: Some((name.to_string(), value)) }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Json> {
+        match (&self.binding, self.parent) {
+            (Some((binding, value)), _) if binding.eq(name) => Some(value.to_owned()),
+            (_, Some(parent)) => parent.lookup(name),
+            _ => self.ctx.lookup(name),
+        }
+    }
+}
+
 impl Template {
-    pub fn print(&self, ctx: &Context) -> String {
-        let mut json = Template::convert(&self.content, ctx);
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn extends(&self) -> Option<&str> {
+        self.extends.as_deref()
+    }
+
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    pub fn array_merge(&self) -> ArrayMerge {
+        self.array_merge
+    }
+
+    pub fn content(&self) -> &Toml {
+        &self.content
+    }
+
+    //replaces the content with the one resolved from extended templates and included fragments,
+    //the schema defaults to the release one and is relative to the directory of the configuration file
+    pub fn resolve(&mut self, content: Toml, release_schema: Option<&Path>, conf_dir: &Path) {
+        self.content = content;
+        self.extends = None;
+        self.include = vec![];
+        self.schema = self.schema.take().or_else(|| release_schema.map(Path::to_path_buf))
+            .map(|schema| conf_dir.join(schema));
+    }
+
+    //reports (key path, text, problem) for every invalid placeholder of the content without rendering it
+    pub fn check(toml: &Toml, path: &str, bound: &[String]) -> Vec<(String, String, String)> {
+        match toml {
+            Toml::String(s) => {
+                let problem = |message: String| vec![(path.to_string(), s.to_owned(), message)];
+                match placeholder::parse(s) {
+                    Err(err) => problem(err),
+                    Ok(segments) => segments.iter().filter_map(|segment| match segment {
+                        Segment::Placeholder(expr) => Some(expr),
+                        Segment::Text(_) => None,
+                    }).flat_map(|expr| {
+                        let mut problems: Vec<(String, String, String)> = vec![];
+                        if !Context::is_placeholder(&expr.name) && !bound.contains(&expr.name) {
+                            problems.extend(problem(format!("Unknown placeholder '{{{}}}'", expr.name)));
+                        }
+                        for pipe in &expr.pipes {
+                            if let Pipe::Filter(f) = pipe {
+                                if let Err(err) = filter::check(f) {
+                                    problems.extend(problem(err));
+                                }
+                            }
+                        }
+                        problems
+                    }).collect(),
+                }
+            }
+            Toml::Array(arr) => arr.iter().enumerate().flat_map(|(i, v)| {
+                let path = format!("{}[{}]", path, i);
+                match v {
+                    Toml::Table(table) if table.contains_key("_each") => {
+                        let mut item_bound = bound.to_vec();
+                        item_bound.push(table.get("_as").and_then(Toml::as_str).unwrap_or("item").to_string());
+                        table.iter().flat_map(|(k, v)| {
+                            let path = format!("{}.{}", path, k);
+                            match k.as_str() {
+                                "_as" => vec![],
+                                "_each" => Template::check(v, &path, bound),
+                                _ => Template::check(v, &path, &item_bound),
+                            }
+                        }).collect::<Vec<_>>()
+                    }
+                    _ => Template::check(v, &path, bound),
+                }
+            }).collect(),
+            Toml::Table(table) => table.iter().flat_map(|(k, v)| {
+                let path = format!("{}.{}", path, k);
+                match k.as_str() {
+                    "_each" | "_as" => vec![(path.to_owned(), k.to_owned(), format!("'{}' is only allowed in array elements", k))],
+                    _ => Template::check(v, &path, bound),
+                }
+            }).collect(),
+            _ => vec![],
+        }
+    }
+
+    //returns None when the template is excluded by a top level `_if`
+    pub fn print(&self, ctx: &Context) -> Result<Option<Json>, String> {
+        let json = Template::convert(&self.content, &Scope::new(ctx), "content")
+            .map_err(|err| format!("Template '{}': {}", self.id, err))?;
+        let mut json = match json {
+            Some(json) => json,
+            None => return Ok(None),
+        };
         if let Some(template) = json.as_object_mut() {
+            let injected: Vec<String> = INJECTED_KEYS.iter()
+                .filter(|key| !template.contains_key(**key))
+                .map(|key| key.to_string())
+                .collect();
             if let Some(jiras) = template.entry("jiras")
                 .or_insert_with(|| Json::Array(vec![]))
-                .as_array_mut() {
+                .as_array_mut()
+                .filter(|_| !self.is_placeholder("jiras")) {
                 for jira in &ctx.jiras {
                     jiras.push(Json::String(jira.to_owned()))
                 }
             }
             if let Some(wip_jiras) = template.entry("wip-jiras")
                 .or_insert_with(|| Json::Array(vec![]))
-                .as_array_mut() {
+                .as_array_mut()
+                .filter(|_| !self.is_placeholder("wip-jiras")) {
                 for jira in &ctx.wip_jiras {
                     wip_jiras.push(Json::String(jira.to_owned()))
                 }
             }
-            if let Json::String(date) = template.entry("release-date")
-                .or_insert_with(|| Json::String(String::from(""))) {
-                if date.is_empty() { date.push_str(
-                    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string().as_str())
-                }
+            let date = template.entry("release-date").or_insert_with(|| Json::String(String::from("")));
+            if date.as_str() == Some("") {
+                *date = Json::String(self.release_date(ctx)?);
             }
+            let template = std::mem::take(template);
+            json = Json::Object(self.place(template, &injected)?);
         }
-        format!("{}", json)
+        Ok(Some(json))
     }
 
-    fn convert(toml: &Toml, ctx: &Context) -> Json {
-        match toml {
-            Toml::String(s) => Json::String(Template::interpolate(s.to_owned(), ctx)),
+    //the release date expression resolved in the template's timezone and formatted with its date-format
+    fn release_date(&self, ctx: &Context) -> Result<String, String> {
+        ctx.release_date.resolve(Utc::now(), self.timezone.0)
+            .map(|date| date.format(&self.date_format.0).to_string())
+            .map_err(|err| format!("Template '{}': {}", self.id, err))
+    }
+
+    //moves the injected keys, appended at the end of the template, to the configured position
+    fn place(&self, mut template: serde_json::Map<String, Json>, injected: &[String]) -> Result<serde_json::Map<String, Json>, String> {
+        let (anchor, after) = match &self.inject_at {
+            InjectAt::End => return Ok(template),
+            InjectAt::Start => (None, false),
+            InjectAt::Before(key) => (Some(key), false),
+            InjectAt::After(key) => (Some(key), true),
+        };
+        if let Some(key) = anchor.filter(|key| !template.contains_key(key.as_str())) {
+            return Err(format!("Template '{}': inject-at key '{}' not found", self.id, key));
+        }
+        let mut injected_values: Vec<(String, Json)> = vec![];
+        for key in injected {
+            if let Some(value) = template.get(key) {
+                injected_values.push((key.to_owned(), value.to_owned()));
+            }
+        }
+        let mut placed = serde_json::Map::new();
+        if anchor.is_none() {
+            placed.extend(injected_values.drain(..));
+        }
+        for (key, value) in std::mem::take(&mut template) {
+            if injected.contains(&key) { continue; }
+            let is_anchor = anchor == Some(&key);
+            if is_anchor && !after { placed.extend(injected_values.drain(..)); }
+            placed.insert(key, value);
+            if is_anchor && after { placed.extend(injected_values.drain(..)); }
+        }
+        Ok(placed)
+    }
+
+    //a top level key set by a placeholder is already populated by the template and must not be appended to
+    fn is_placeholder(&self, key: &str) -> bool {
+        matches!(self.content.get(key), Some(Toml::String(_)))
+    }
+
+    //returns None when the value is a table excluded by its `_if` key
+    fn convert(toml: &Toml, scope: &Scope, path: &str) -> Result<Option<Json>, String> {
+        Ok(Some(match toml {
+            Toml::String(s) => Template::substitute(s, scope)
+                .map_err(|err| format!("{} at '{}'", err, path))?,
             Toml::Integer(i) => Json::Number((*i).into()),
             Toml::Float(f) => {
                 let n = serde_json::Number::from_f64(*f).expect("float infinite and nan not allowed");
                 Json::Number(n)
             }
             Toml::Boolean(b) => Json::Bool(*b),
-            Toml::Array(arr) => Json::Array(arr.iter().map(|v| Template::convert(v, ctx)).collect()),
+            Toml::Array(arr) => {
+                let mut values: Vec<Json> = vec![];
+                for (i, v) in arr.iter().enumerate() {
+                    let path = format!("{}[{}]", path, i);
+                    match v {
+                        Toml::Table(table) if table.contains_key("_each") => {
+                            values.extend(Template::expand(table, scope, &path)?)
+                        }
+                        _ => values.extend(Template::convert(v, scope, &path)?),
+                    }
+                }
+                Json::Array(values)
+            }
             Toml::Table(table) => {
-                Json::Object(table.into_iter().map(|(k, v)| (k.to_owned(), Template::convert(v, ctx))).collect())
+                if let Some(condition) = table.get("_if") {
+                    let path = format!("{}._if", path);
+                    if !filter::truthy(&Template::convert(condition, scope, &path)?.unwrap_or(Json::Null)) {
+                        return Ok(None);
+                    }
+                }
+                let mut object = serde_json::Map::new();
+                for (k, v) in table {
+                    let path = format!("{}.{}", path, k);
+                    match k.as_str() {
+                        "_if" => {}
+                        "_each" | "_as" => return Err(format!("'{}' is only allowed in array elements at '{}'", k, path)),
+                        _ => if let Some(value) = Template::convert(v, scope, &path)? {
+                            object.insert(k.to_owned(), value);
+                        },
+                    }
+                }
+                Json::Object(object)
             }
             Toml::Datetime(dt) => Json::String(dt.to_string()),
+        }))
+    }
+
+    //expands an array element with an `_each` key into one element per item, bound to the `_as` name (default: item)
+    fn expand(table: &toml::value::Table, scope: &Scope, path: &str) -> Result<Vec<Json>, String> {
+        let items = match table.get("_each") {
+            Some(Toml::String(s)) => Template::substitute(s, scope)
+                .map_err(|err| format!("{} at '{}._each'", err, path))?,
+            _ => return Err(format!("'_each' must be a placeholder string at '{}._each'", path)),
+        };
+        let name = match table.get("_as") {
+            Some(Toml::String(name)) => name.as_str(),
+            Some(_) => return Err(format!("'_as' must be a string at '{}._as'", path)),
+            None => "item",
+        };
+        let items = match items {
+            Json::Array(items) => items,
+            Json::Null => vec![],
+            other => vec![other],
+        };
+        let element = Toml::Table(table.iter()
+            .filter(|(k, _)| k.as_str() != "_each" && k.as_str() != "_as")
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect());
+        let mut values: Vec<Json> = vec![];
+        for item in items {
+            values.extend(Template::convert(&element, &scope.bind(name, item), path)?);
         }
+        Ok(values)
     }
 
-    fn interpolate(text: String, ctx: &Context) -> String {
-        text.replace("{now-version}", ctx.current_version.as_str())
-            .replace("{next-version}", ctx.next_version.as_str())
-            .replace("{tweet}", ctx.tweet.as_str())
-            .replace("{pvt-line-range}", ctx.pvt_line_range.as_str())
+    //renders a text of the configuration other than a template content, e.g. the tweet template, with an optional
+    //bound value
+    pub fn render_text(text: &str, ctx: &Context, binding: Option<(&str, Json)>) -> Result<String, String> {
+        let scope = Scope::new(ctx);
+        match binding {
+            Some((name, value)) => Template::interpolate(placeholder::parse(text)?, &scope.bind(name, value)),
+            None => Template::interpolate(placeholder::parse(text)?, &scope),
+        }
+    }
+
+    //a string holding a single placeholder keeps the type of its value, otherwise it is interpolated into a string
+    fn substitute(text: &str, scope: &Scope) -> Result<Json, String> {
+        let segments = placeholder::parse(text)?;
+        match segments.as_slice() {
+            [Segment::Placeholder(expr)] => Template::evaluate(expr, scope),
+            _ => Template::interpolate(segments, scope).map(Json::String),
+        }
+    }
+
+    fn interpolate(segments: Vec<Segment>, scope: &Scope) -> Result<String, String> {
+        let mut result = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => result.push_str(&text),
+                Segment::Placeholder(expr) => result.push_str(&Template::render(&Template::evaluate(&expr, scope)?)),
+            }
+        }
+        Ok(result)
+    }
+
+    fn evaluate(expr: &Expr, scope: &Scope) -> Result<Json, String> {
+        let mut value = scope.lookup(&expr.name)
+            .ok_or(format!("Unknown placeholder '{{{}}}'", expr.name))?;
+        for pipe in &expr.pipes {
+            value = match pipe {
+                Pipe::Default(default) if Template::render(&value).is_empty() => Json::String(default.to_owned()),
+                Pipe::Default(_) => value,
+                Pipe::Filter(f) => filter::apply(f, value)?,
+            };
+        }
+        Ok(value)
+    }
+
+    fn render(value: &Json) -> String {
+        match value {
+            Json::String(s) => s.to_owned(),
+            Json::Array(values) => values.iter().map(Template::render).collect::<Vec<_>>().join(", "),
+            Json::Null => String::new(),
+            other => other.to_string(),
+        }
     }
 }
 
@@ -101,36 +510,215 @@ impl Template {
 pub struct Release {
     pub name: String,
     templates: Vec<Template>,
+    pub schema: Option<PathBuf>,
+    #[serde(default, rename = "jira-pattern")]
+    jira_pattern: JiraPattern,
+    #[serde(default, rename = "wip-marker")]
+    wip_marker: WipMarker,
+    #[serde(rename = "tag-pattern")]
+    tag_pattern: Option<TagPattern>,
+    #[serde(default)]
+    bump: Bump,
+    #[serde(rename = "pvt-markers")]
+    pvt_markers: Option<PvtMarkers>,
+    #[serde(rename = "main-pattern")]
+    main_pattern: Option<String>,
+    pub tracker: Option<TrackerConf>,
+    #[serde(rename = "current-version-source")]
+    pub current_version_source: Option<VersionSource>,
+    #[serde(rename = "tweet-template")]
+    tweet_template: Option<String>,
+    #[serde(rename = "tweet-max-length")]
+    tweet_max_length: Option<usize>,
+    #[serde(rename = "tweet-file")]
+    pub tweet_file: Option<PathBuf>,
+    #[serde(default)]
+    interactive: bool,
+    #[serde(default)]
+    parameters: Vec<Parameter>,
+    #[serde(default)]
+    vars: serde_json::Map<String, Json>,
+    #[serde(skip)]
+    pub origin: PathBuf,
 }
 
 impl Release {
     pub fn templates(&self) -> &Vec<Template> {
         &self.templates
     }
+
+    pub fn templates_mut(&mut self) -> &mut Vec<Template> {
+        &mut self.templates
+    }
+
+    pub fn template(&self, id: &str) -> Option<&Template> {
+        self.templates.iter().find(|t| t.id.eq(id))
+    }
+
+    pub fn jira_pattern(&self) -> &JiraPattern {
+        &self.jira_pattern
+    }
+
+    pub fn wip_marker(&self) -> &WipMarker {
+        &self.wip_marker
+    }
+
+    pub fn tag_pattern(&self) -> Option<&TagPattern> {
+        self.tag_pattern.as_ref()
+    }
+
+    pub fn bump(&self) -> Bump {
+        self.bump
+    }
+
+    pub fn pvt_markers(&self) -> Option<&PvtMarkers> {
+        self.pvt_markers.as_ref()
+    }
+
+    pub fn main_pattern(&self) -> Option<&str> {
+        self.main_pattern.as_deref()
+    }
+
+    pub fn tweet_template(&self) -> &str {
+        self.tweet_template.as_deref().unwrap_or(DEFAULT_TWEET_TEMPLATE)
+    }
+
+    pub fn tweet_max_length(&self) -> usize {
+        self.tweet_max_length.unwrap_or(DEFAULT_TWEET_MAX_LENGTH)
+    }
+
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.iter().find(|p| p.name().eq(name))
+    }
+
+    //the configured default of a release value that is not given, which fails when the parameter is required
+    pub fn fallback(&self, name: &str) -> Result<Option<&str>, String> {
+        match self.parameter(name) {
+            Some(parameter) if parameter.required() => Err(format!(
+                "Missing required parameter '{}' of release '{}', use {} or --interactive",
+                name, self.name, parameter.options())),
+            Some(parameter) => Ok(parameter.default()),
+            None => Ok(None),
+        }
+    }
+
+    pub fn vars(&self) -> &serde_json::Map<String, Json> {
+        &self.vars
+    }
+
+    //checks a value against the pattern of its parameter, if any
+    pub fn check(&self, name: &str, value: &str) -> Result<(), String> {
+        self.parameter(name).map_or(Ok(()), |parameter| parameter.check(value))
+    }
 }
 
 pub trait Output {
     fn print(&self, templates: &[Template], ctx: &Context) -> Result<(), String>;
 }
 
-pub struct Console {}
+pub struct Console {
+    format: Format,
+}
 
 impl Console {
-    pub fn new() -> Console {
-        Console {}
+    pub fn new(format: Format) -> Console {
+        Console { format }
     }
 }
 
 impl Output for Console {
     fn print(&self, templates: &[Template], ctx: &Context) -> Result<(), String> {
-        let mut json = String::from("[");
-        for template in templates {
-            if json.len() != 1 { json.push_str(", "); }
-            json.push_str(&template.print(ctx));
+        println!("{}", self.format.write_all(render(templates, ctx, self.format)?)?);
+        Ok(())
+    }
+}
+
+fn render(templates: &[Template], ctx: &Context, format: Format) -> Result<Vec<Json>, String> {
+    Ok(render_each(templates, ctx, format)?.into_iter().map(|(_, json)| json).collect())
+}
+
+//renders every template and validates it against its schema and the output format so that nothing is written when
+//any is invalid
+fn render_each<'a>(templates: &'a [Template], ctx: &Context, format: Format)
+                   -> Result<Vec<(&'a Template, Json)>, String> {
+    let mut rendered: Vec<(&Template, Json)> = vec![];
+    let mut violations: Vec<String> = vec![];
+    for template in templates {
+        if let Some(json) = template.print(ctx)? {
+            if let Some(file) = &template.schema {
+                violations.extend(schema::validate(&schema::load(file)?, &json).into_iter()
+                    .map(|violation| format!("Template '{}' does not match schema at {}", template.id, violation)));
+            }
+            violations.extend(format.unwritable(&json).into_iter().map(|path| format!(
+                "Template '{}': null value at '{}' can not be written as {}", template.id, path, format.extension())));
+            rendered.push((template, json));
         }
-        json.push(']');
+    }
+    if !violations.is_empty() {
+        return Err(violations.join("\n"));
+    }
+    Ok(rendered)
+}
+
+//Writes the release to a file, or one `<template id>.<format extension>` file per template into a directory when split
+pub struct File {
+    path: PathBuf,
+    split: bool,
+    force: bool,
+    format: Format,
+}
+
+impl File {
+    pub fn new(path: PathBuf, split: bool, force: bool, format: Format) -> File {
+        File { path, split, force, format }
+    }
+
+    //the content is written to a temporary file in the target directory and renamed over the target
+    fn write(&self, path: &Path, content: &str) -> Result<(), String> {
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+        let mut file = NamedTempFile::new_in(dir).map_err(|err|
+            format!("Unable to create temporary file in '{}': {}", dir.display(), err))?;
+        writeln!(file, "{}", content).map_err(|err|
+            format!("Unable to write '{}': {}", path.display(), err))?;
+        let persisted = if self.force { file.persist(path) } else { file.persist_noclobber(path) };
+        persisted.map_err(|err| format!("Unable to write '{}': {}", path.display(), err.error))?;
+        println!("Created: {}", path.display());
+        Ok(())
+    }
 
-        println!("{}", json);
+    fn check_clobber(&self, path: &Path) -> Result<(), String> {
+        if !self.force && path.exists() {
+            return Err(format!("Output file '{}' already exists, use --force to overwrite it", path.display()));
+        }
+        Ok(())
+    }
+}
+
+impl Output for File {
+    fn print(&self, templates: &[Template], ctx: &Context) -> Result<(), String> {
+        if !self.split {
+            self.check_clobber(&self.path)?;
+            return self.write(&self.path, &self.format.write_all(render(templates, ctx, self.format)?)?);
+        }
+        let mut files: Vec<(PathBuf, String)> = vec![];
+        for (template, json) in render_each(templates, ctx, self.format)? {
+            let path = self.path.join(format!("{}.{}", template.id, self.format.extension()));
+            self.check_clobber(&path)?;
+            files.push((path, self.format.write(&json)?));
+        }
+        std::fs::create_dir_all(&self.path).map_err(|err|
+            format!("Unable to create output directory '{}': {}", self.path.display(), err))?;
+        for (path, json) in files {
+            self.write(&path, &json)?;
+        }
         Ok(())
     }
 }
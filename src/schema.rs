@@ -0,0 +1,227 @@
+//JSON Schema (draft 7) validation of rendered templates, supporting the keywords:
+//type, enum, const, required, properties, additionalProperties, items, minItems, maxItems, uniqueItems,
+//minLength, maxLength, pattern, minimum, maximum, exclusiveMinimum, exclusiveMaximum, multipleOf,
+//allOf, anyOf, oneOf, not and local $ref ("#/definitions/<name>"), any other keyword fails the schema
+use regex::Regex;
+use serde_json::Value as Json;
+use std::path::Path;
+
+const KEYWORDS: &[&str] = &[
+    "type", "enum", "const", "required", "properties", "additionalProperties", "items", "minItems", "maxItems",
+    "uniqueItems", "minLength", "maxLength", "pattern", "minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum",
+    "multipleOf", "allOf", "anyOf", "oneOf", "not", "$ref",
+];
+
+//keywords without effect on validation
+const ANNOTATIONS: &[&str] = &[
+    "$schema", "$id", "$comment", "title", "description", "default", "examples", "definitions", "readOnly",
+    "writeOnly",
+];
+
+pub fn load(file: &Path) -> Result<Json, String> {
+    let content = std::fs::read_to_string(file).map_err(|err|
+        format!("Unable to read schema '{}': {}", file.display(), err))?;
+    let schema = serde_json::from_str(&content).map_err(|err| format!("Invalid schema '{}': {}", file.display(), err))?;
+    supported(&schema, "#").map_err(|err| format!("Invalid schema '{}': {}", file.display(), err))?;
+    Ok(schema)
+}
+
+//fails on the first keyword that would not be validated, so that a schema is never silently weakened
+fn supported(schema: &Json, pointer: &str) -> Result<(), String> {
+    let schema = match schema {
+        Json::Object(schema) => schema,
+        _ => return Ok(()),
+    };
+    for (keyword, value) in schema {
+        let pointer = format!("{}/{}", pointer, keyword);
+        if !KEYWORDS.contains(&keyword.as_str()) && !ANNOTATIONS.contains(&keyword.as_str()) {
+            return Err(format!("unsupported keyword '{}' at {} (supported keywords: {})",
+                               keyword, pointer, KEYWORDS.join(", ")));
+        }
+        match (keyword.as_str(), value) {
+            ("properties", Json::Object(schemas)) | ("definitions", Json::Object(schemas)) => schemas.iter()
+                .try_for_each(|(name, schema)| supported(schema, &format!("{}/{}", pointer, name)))?,
+            ("items", Json::Array(schemas)) | ("allOf", Json::Array(schemas)) | ("anyOf", Json::Array(schemas))
+            | ("oneOf", Json::Array(schemas)) => schemas.iter().enumerate()
+                .try_for_each(|(i, schema)| supported(schema, &format!("{}/{}", pointer, i)))?,
+            ("items", schema) | ("additionalProperties", schema) | ("not", schema) => supported(schema, &pointer)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+//returns every violation as "<json pointer>: <problem>"
+pub fn validate(schema: &Json, instance: &Json) -> Vec<String> {
+    let mut violations: Vec<String> = vec![];
+    check(schema, schema, instance, "#", &mut violations);
+    violations
+}
+
+fn check(root: &Json, schema: &Json, instance: &Json, pointer: &str, violations: &mut Vec<String>) {
+    let schema = match schema {
+        Json::Bool(true) => return,
+        Json::Bool(false) => return violations.push(format!("{}: no value is allowed", pointer)),
+        Json::Object(schema) => schema,
+        _ => return violations.push(format!("{}: invalid schema, expected an object or a boolean", pointer)),
+    };
+    if let Some(reference) = schema.get("$ref").and_then(Json::as_str) {
+        match reference.strip_prefix('#').map(|path| root.pointer(path)) {
+            Some(Some(referenced)) => check(root, referenced, instance, pointer, violations),
+            _ => violations.push(format!("{}: unresolvable $ref '{}'", pointer, reference)),
+        }
+        return;
+    }
+    let mut problems: Vec<String> = vec![];
+    let mut violation = |problem: String| problems.push(problem);
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Json::String(t) => vec![t.as_str()],
+            Json::Array(types) => types.iter().filter_map(Json::as_str).collect(),
+            _ => vec![],
+        };
+        if !types.iter().any(|t| is_type(instance, t)) {
+            violation(format!("expected {} but found {}", types.join(" or "), type_name(instance)));
+        }
+    }
+    if let Some(Json::Array(values)) = schema.get("enum") {
+        if !values.contains(instance) {
+            violation(format!("{} is not one of {}", instance, Json::Array(values.to_owned())));
+        }
+    }
+    if let Some(value) = schema.get("const") {
+        if value != instance {
+            violation(format!("expected {} but found {}", value, instance));
+        }
+    }
+    match instance {
+        Json::Object(object) => {
+            if let Some(Json::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Json::as_str) {
+                    if !object.contains_key(key) {
+                        violation(format!("missing required property '{}'", key));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Json::as_object);
+            for (key, value) in object {
+                let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                    (Some(property), _) => check(root, property, value, &pointer, violations),
+                    (None, Some(Json::Bool(false))) =>
+                        violations.push(format!("{}: additional property is not allowed", pointer)),
+                    (None, Some(additional)) => check(root, additional, value, &pointer, violations),
+                    (None, None) => {}
+                }
+            }
+        }
+        Json::Array(values) => {
+            if let Some(min) = schema.get("minItems").and_then(Json::as_u64) {
+                if (values.len() as u64) < min { violation(format!("expected at least {} items but found {}", min, values.len())); }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Json::as_u64) {
+                if values.len() as u64 > max { violation(format!("expected at most {} items but found {}", max, values.len())); }
+            }
+            if schema.get("uniqueItems") == Some(&Json::Bool(true)) {
+                for (i, value) in values.iter().enumerate() {
+                    if values[..i].contains(value) { violation(format!("duplicate item {}", value)); }
+                }
+            }
+            for (i, value) in values.iter().enumerate() {
+                let item = match schema.get("items") {
+                    Some(Json::Array(items)) => items.get(i),
+                    items => items,
+                };
+                if let Some(item) = item {
+                    check(root, item, value, &format!("{}/{}", pointer, i), violations);
+                }
+            }
+        }
+        Json::String(s) => {
+            let length = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Json::as_u64) {
+                if length < min { violation(format!("expected at least {} characters but found {}", min, length)); }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Json::as_u64) {
+                if length > max { violation(format!("expected at most {} characters but found {}", max, length)); }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Json::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if !regex.is_match(s) => violation(format!("\"{}\" does not match pattern '{}'", s, pattern)),
+                    Ok(_) => {}
+                    Err(err) => violation(format!("invalid pattern '{}': {}", pattern, err)),
+                }
+            }
+        }
+        Json::Number(n) => {
+            let n = n.as_f64().unwrap_or(f64::NAN);
+            let bound = |keyword: &str| schema.get(keyword).and_then(Json::as_f64);
+            if let Some(min) = bound("minimum") {
+                if n < min { violation(format!("{} is less than the minimum {}", n, min)); }
+            }
+            if let Some(max) = bound("maximum") {
+                if n > max { violation(format!("{} is greater than the maximum {}", n, max)); }
+            }
+            if let Some(min) = bound("exclusiveMinimum") {
+                if n <= min { violation(format!("{} is not greater than {}", n, min)); }
+            }
+            if let Some(max) = bound("exclusiveMaximum") {
+                if n >= max { violation(format!("{} is not less than {}", n, max)); }
+            }
+            if let Some(multiple) = bound("multipleOf") {
+                if (n / multiple).fract() != 0.0 { violation(format!("{} is not a multiple of {}", n, multiple)); }
+            }
+        }
+        _ => {}
+    }
+    violations.extend(problems.into_iter().map(|problem| format!("{}: {}", pointer, problem)));
+    let matching = |schemas: &Vec<Json>| schemas.iter()
+        .filter(|schema| validate_with(root, schema, instance).is_empty())
+        .count();
+    if let Some(Json::Array(schemas)) = schema.get("allOf") {
+        for schema in schemas {
+            check(root, schema, instance, pointer, violations);
+        }
+    }
+    if let Some(Json::Array(schemas)) = schema.get("anyOf") {
+        if matching(schemas) == 0 {
+            violations.push(format!("{}: does not match any of the anyOf schemas", pointer));
+        }
+    }
+    if let Some(Json::Array(schemas)) = schema.get("oneOf") {
+        let count = matching(schemas);
+        if count != 1 {
+            violations.push(format!("{}: expected to match exactly one oneOf schema but matched {}", pointer, count));
+        }
+    }
+    if let Some(not) = schema.get("not") {
+        if validate_with(root, not, instance).is_empty() {
+            violations.push(format!("{}: must not match the 'not' schema", pointer));
+        }
+    }
+}
+
+fn validate_with(root: &Json, schema: &Json, instance: &Json) -> Vec<String> {
+    let mut violations: Vec<String> = vec![];
+    check(root, schema, instance, "#", &mut violations);
+    violations
+}
+
+fn is_type(instance: &Json, name: &str) -> bool {
+    match name {
+        "integer" => instance.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => instance.is_number(),
+        other => type_name(instance).eq(other),
+    }
+}
+
+fn type_name(instance: &Json) -> &str {
+    match instance {
+        Json::Null => "null",
+        Json::Bool(_) => "boolean",
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
@@ -0,0 +1,98 @@
+//Parameter stores holding the deployed version of a release: AWS SSM through the aws cli, or a json file of
+//parameter names and values with the same lookup semantics to work offline
+use serde::Deserialize;
+use serde_json::Value as Json;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::version::Version;
+
+pub trait ParameterStore {
+    fn get(&self, name: &str) -> Result<String, String>;
+}
+
+//The source of the current version of a release, e.g. { type = "ssm", path = "/uat/myapp/version" } or
+//{ type = "file", file = "parameters.json", path = "/uat/myapp/version" } relative to the configuration file
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum VersionSource {
+    Ssm {
+        path: String,
+        region: Option<String>,
+        profile: Option<String>,
+    },
+    File {
+        path: String,
+        file: PathBuf,
+    },
+}
+
+impl VersionSource {
+    pub fn resolve(&mut self, conf_dir: &Path) {
+        if let VersionSource::File { file, .. } = self {
+            *file = conf_dir.join(&file);
+        }
+    }
+
+    pub fn version(&self) -> Result<Version, String> {
+        let (store, path): (Box<dyn ParameterStore>, &str) = match self {
+            VersionSource::Ssm { path, region, profile } =>
+                (Box::new(Ssm { region: region.to_owned(), profile: profile.to_owned() }), path),
+            VersionSource::File { path, file } => (Box::new(ParameterFile { file: file.to_owned() }), path),
+        };
+        Version::parse(store.get(path)?.trim()).map_err(|err| format!("Parameter '{}': {}", path, err))
+    }
+}
+
+//GetParameter with the credentials and configuration of the aws cli
+pub struct Ssm {
+    region: Option<String>,
+    profile: Option<String>,
+}
+
+impl ParameterStore for Ssm {
+    fn get(&self, name: &str) -> Result<String, String> {
+        let mut command = Command::new("aws");
+        command.args(["ssm", "get-parameter", "--with-decryption", "--output", "json", "--name", name]);
+        if let Some(region) = &self.region {
+            command.args(["--region", region.as_str()]);
+        }
+        if let Some(profile) = &self.profile {
+            command.args(["--profile", profile.as_str()]);
+        }
+        let output = command.output().map_err(|err| format!("Unable to run the aws cli: {}", err))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("ParameterNotFound") {
+            return Err(not_found(name, "SSM"));
+        } else if !output.status.success() {
+            return Err(format!("SSM parameter '{}': aws ssm get-parameter failed: {}", name, stderr.trim()));
+        }
+        let json: Json = serde_json::from_slice(&output.stdout)
+            .map_err(|err| format!("SSM parameter '{}': invalid aws cli output: {}", name, err))?;
+        json["Parameter"]["Value"].as_str().map(str::to_string)
+            .ok_or_else(|| format!("SSM parameter '{}': aws cli output has no Parameter.Value", name))
+    }
+}
+
+//parameters of a json object, e.g. {"/uat/myapp/version": "1.4.2"}
+pub struct ParameterFile {
+    file: PathBuf,
+}
+
+impl ParameterStore for ParameterFile {
+    fn get(&self, name: &str) -> Result<String, String> {
+        let content = std::fs::read_to_string(&self.file)
+            .map_err(|err| format!("Unable to read parameter file '{}': {}", self.file.display(), err))?;
+        let json: Json = serde_json::from_str(&content)
+            .map_err(|err| format!("Invalid parameter file '{}': {}", self.file.display(), err))?;
+        match json.get(name) {
+            Some(Json::String(value)) => Ok(value.to_owned()),
+            Some(_) => Err(format!("Parameter '{}' of '{}' is not a string", name, self.file.display())),
+            None => Err(not_found(name, &self.file.display().to_string())),
+        }
+    }
+}
+
+fn not_found(name: &str, store: &str) -> String {
+    format!("Parameter '{}' not found in {}", name, store)
+}
@@ -0,0 +1,143 @@
+//Issue tracker lookups of the release jiras, from the Jira REST api or from a directory of issue files holding
+//the same json as the api responses (`<dir>/<key>.json`) to work offline
+use serde::Deserialize;
+use serde_json::Value as Json;
+use std::path::{Path, PathBuf};
+
+use crate::git::JiraPattern;
+
+//fields of an issue available to placeholders as {jira.<key>.<field>}
+pub const ISSUE_FIELDS: &[&str] = &["summary", "description", "status", "fix-versions"];
+
+const DEFAULT_TOKEN_ENV: &str = "JIRA_TOKEN";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Issue {
+    pub key: String,
+    pub summary: String,
+    pub description: String,
+    pub status: String,
+    pub fix_versions: Vec<String>,
+}
+
+impl Issue {
+    fn from_json(key: &str, json: &Json) -> Issue {
+        let fields = &json["fields"];
+        let text = |value: &Json| value.as_str().unwrap_or_default().to_string();
+        Issue {
+            key: key.to_string(),
+            summary: text(&fields["summary"]),
+            description: text(&fields["description"]),
+            status: text(&fields["status"]["name"]),
+            fix_versions: fields["fixVersions"].as_array().map_or(vec![], |versions|
+                versions.iter().map(|version| text(&version["name"])).collect()),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<Json> {
+        match name {
+            "summary" => Some(Json::String(self.summary.to_owned())),
+            "description" => Some(Json::String(self.description.to_owned())),
+            "status" => Some(Json::String(self.status.to_owned())),
+            "fix-versions" => Some(self.fix_versions.iter().map(|v| Json::String(v.to_owned())).collect()),
+            _ => None,
+        }
+    }
+}
+
+pub trait Tracker {
+    fn issue(&self, key: &str) -> Result<Issue, String>;
+}
+
+//The tracker of a release, e.g. { type = "jira", url = "https://jira.example.com" } or
+//{ type = "dir", path = "issues" } relative to the configuration file
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TrackerConf {
+    Jira {
+        url: String,
+        #[serde(rename = "token-env")]
+        token_env: Option<String>,
+    },
+    Dir {
+        path: PathBuf,
+    },
+}
+
+impl TrackerConf {
+    pub fn resolve(&mut self, conf_dir: &Path) {
+        if let TrackerConf::Dir { path } = self {
+            *path = conf_dir.join(&path);
+        }
+    }
+
+    pub fn tracker(&self) -> Box<dyn Tracker> {
+        match self {
+            TrackerConf::Jira { url, token_env } => Box::new(JiraRest {
+                url: url.trim_end_matches('/').to_string(),
+                token: std::env::var(token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV)).ok(),
+            }),
+            TrackerConf::Dir { path } => Box::new(IssueDir { path: path.to_owned() }),
+        }
+    }
+}
+
+//issues of the Jira REST api (v2) with an optional bearer token
+pub struct JiraRest {
+    url: String,
+    token: Option<String>,
+}
+
+impl Tracker for JiraRest {
+    fn issue(&self, key: &str) -> Result<Issue, String> {
+        let url = format!("{}/rest/api/2/issue/{}?fields=summary,description,status,fixVersions", self.url, encode(key));
+        let mut request = ureq::get(&url).set("Accept", "application/json");
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+        let body = request.call().map_err(|err| format!("Jira '{}': request to {} failed: {}", key, url, err))?
+            .into_string().map_err(|err| format!("Jira '{}': unable to read the response of {}: {}", key, url, err))?;
+        let json: Json = serde_json::from_str(&body)
+            .map_err(|err| format!("Jira '{}': invalid response of {}: {}", key, url, err))?;
+        Ok(Issue::from_json(key, &json))
+    }
+}
+
+//the key as a url path segment, any byte but the unreserved ones percent encoded
+fn encode(key: &str) -> String {
+    key.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+//issues read from `<path>/<key>.json`
+pub struct IssueDir {
+    path: PathBuf,
+}
+
+impl Tracker for IssueDir {
+    fn issue(&self, key: &str) -> Result<Issue, String> {
+        let file = self.path.join(format!("{}.json", key));
+        let content = std::fs::read_to_string(&file)
+            .map_err(|err| format!("Jira '{}': unable to read '{}': {}", key, file.display(), err))?;
+        let json: Json = serde_json::from_str(&content)
+            .map_err(|err| format!("Jira '{}': invalid issue file '{}': {}", key, file.display(), err))?;
+        Ok(Issue::from_json(key, &json))
+    }
+}
+
+//issues of the jiras in order, each looked up once
+//issues of the jiras, which must be keys matching the jira pattern before anything is looked up
+pub fn issues(tracker: &dyn Tracker, pattern: &JiraPattern, jiras: &[&String]) -> Result<Vec<Issue>, String> {
+    if let Some(jira) = jiras.iter().find(|jira| !pattern.is_key(jira)) {
+        return Err(format!("Invalid jira '{}', expected a key matching jira-pattern '{}'", jira, pattern));
+    }
+    let mut issues: Vec<Issue> = vec![];
+    for jira in jiras {
+        if !issues.iter().any(|issue| issue.key.eq(*jira)) {
+            issues.push(tracker.issue(jira)?);
+        }
+    }
+    Ok(issues)
+}
@@ -0,0 +1,37 @@
+//Release short description composed from items (commit subjects, jira summaries or lines of a file) with the
+//tweet template of the release, whole items are left out to fit the maximum length before any is cut
+use serde_json::Value as Json;
+
+use crate::release::{Context, Template};
+
+const ELLIPSIS: char = '…';
+
+pub fn compose(template: &str, items: &[String], max_length: usize, ctx: &Context) -> Result<String, String> {
+    let render = |items: Vec<String>| Template::render_text(template, ctx,
+        Some(("items", Json::Array(items.into_iter().map(Json::String).collect()))))
+        .map_err(|err| format!("Invalid tweet-template: {}", err));
+    for count in (1..=items.len()).rev() {
+        let mut kept = items[..count].to_vec();
+        if count < items.len() {
+            kept.push(format!("+{} more", items.len() - count));
+        }
+        let tweet = render(kept)?;
+        if tweet.chars().count() <= max_length {
+            return Ok(tweet);
+        }
+    }
+    Ok(truncate(&render(items.iter().take(1).cloned().collect())?, max_length))
+}
+
+//cut at the last whitespace that fits the ellipsis, or at the maximum length when there is none
+fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(index) if index > 0 => cut[..index].trim_end(),
+        _ => cut.as_str(),
+    };
+    format!("{}{}", cut, ELLIPSIS)
+}
@@ -1,13 +1,23 @@
 //Usage format based on git
+use crate::release::Release;
+
 pub fn main() -> String {
     let usage = r#"Templar. The template release command line tool
 
-Usage: templar [-h | --help] [--version] [--home <path>] <command> <args>
+Usage: templar [-h | --help] [--version] [--home <path>] [--config <path>...] <command> <args>
+
+Commands:
+    init            Create an example configuration file
+    release         Print the templates of a release
+    config          Inspect the configuration
 
 Options:
     -h, --help      Show this screen
     --version       Show version
     --home <path>   Override user's home directory (where '.templar' configuration resides)
+    --config <path> Additional configuration file, layered after the system ($TEMPLAR_SYSTEM_CONF or
+                    /etc/templar/config.toml), user (~/.templar.toml) and project (.templar.toml found
+                    in the current directory or its parents) files. Releases override by name
 "#;
     usage.to_string()
 }
@@ -15,19 +25,114 @@ Options:
 pub fn release() -> String {
     let usage = r#"
 Usage: templar release [-h | --help] <name> [-c | --current <version>] [-n | --next <version>]
-               [-t | --tweet <description>] [-p | --pvt-line-range <range>] [-j | --jiras <jira...>]
-               [-w | --wip-jiras <jira...>] [--parse] [<path>]
+               [-t | --tweet <description>] [-p | --pvt-line-range <range>] [--pvt-file <path>]
+               [--main-class <class>]
+               [-j | --jiras <jira...>] [-w | --wip-jiras <jira...>] [--jiras-from-git [<rev-range>]]
+               [--tweet-from <source>] [--release-date <date>] [--var <name>=<value>...]
+               [--vars-file <path>] [--interactive] [--check [--strict]]
+               [-o | --output <path> [--split] [--force]] [--format <format>] [--parse]
 
 Options:
-    -h, --help                      Show this screen
+    -h, --help                      Show this screen, followed by the parameters the release <name> declares:
+                                    required values, defaults and patterns of the options below
     --home <path>                   Override user's home directory (where '.templar' configuration resides)
-    -c, --current <version>         Current release version [default: 1]
-    -n, --next <version>            Next release version [default: 2]
-    -t, --tweet <description>       Release short description [default: default tweet]
-    -p, --pvt-line-range <range>    The PVT line range [default: 10-20]
+    -c, --current <version>         Current release version [default: version of the release
+                                    current-version-source, or latest version tagged with the release
+                                    tag-pattern, or 1]
+    -n, --next <version>            Next release version, greater than the current one [default: current
+                                    version bumped as configured by the release bump, or 2]
+    -t, --tweet <description>       Release short description [default: summaries of the released jiras in the
+                                    release tracker, or default tweet]
+    --tweet-from <source>           Compose the tweet, unless given with -t, from the git commit subjects since
+                                    the latest tag, the summaries of the released jiras (jira) or the lines
+                                    of the release tweet-file (file) with the release tweet-template, cut to
+                                    the release tweet-max-length [default max length: 280]
+    -p, --pvt-line-range <range>    The PVT line range [default: lines between the release pvt-markers in the
+                                    repository, or 10-20]
+    --pvt-file <path>               The PVT file, checked by --check [default: file holding the release
+                                    pvt-markers in the repository]
+    --main-class <class>            The main class [default: package and name of the file matching the release
+                                    main-pattern in the repository]
     -j, --jiras <jira...>           The jiras released [default: ]
     -w, --wip-jiras <jira...>       The work in progress jiras in the released [default: ]
+    --jiras-from-git [<rev-range>]  Add the jiras mentioned by the commit messages of the revision range, the ones
+                                    of commits marked as work in progress (wip-marker) to the wip jiras
+                                    [default: <latest tag>..HEAD]
+    --release-date <date>           Date injected into an empty 'release-date': now, today, tomorrow, yesterday,
+                                    next <weekday>, +<n><m|h|d|w> or <n><m|h|d|w> ago optionally followed by
+                                    HH:MM, or an ISO date, date time or timestamp [default: now]
+    --var <name>=<value>            Value of a variable declared in the release vars, repeatable, converted to
+                                    the type of the declared default
+    --vars-file <path>              Json (or toml with the .toml extension) table of values of variables declared
+                                    in the release vars [default: TEMPLAR_VAR_<NAME> environment variables (upper
+                                    case, '_' for '-') converted like --var, or the release vars]
+    --interactive                   Ask for the versions, jiras, tweet, PVT line range, main class and release
+                                    date not given as options, suggesting the values derived from the release
+                                    configuration (an empty answer takes the suggestion) [default: on when the
+                                    release sets interactive = true and the input is a terminal]
+    --check                         Warn about the jiras the PVT code does not reference and the jiras referenced
+                                    by the PVT code or the commit messages that are not part of the release,
+                                    instead of creating the release
+    --strict                        Fail when the check reports any warning
+    -o, --output <path>             Write the release json to a file instead of the console
+    --split                         Write one '<template id>.<format>' file per template into the output directory
+    --force                         Overwrite existing output files
+    --format <format>               Output format: compact, pretty (indented) or canonical (sorted keys and
+                                    stable number formatting) json array, ndjson (one json object per line),
+                                    yaml or toml (a 'templates' array of tables) [default: compact]
     --parse                         Parses release options and prints them without creating the release
 "#;
     usage.to_string()
-}
\ No newline at end of file
+}
+
+//the parameters declared by a release, shown after the release usage
+pub fn parameters(release: &Release) -> String {
+    if release.parameters().is_empty() {
+        return String::new();
+    }
+    let mut usage = format!("Parameters of release '{}':\n", release.name);
+    for parameter in release.parameters() {
+        let mut details: Vec<String> = parameter.help().map(str::to_string).into_iter().collect();
+        if parameter.required() {
+            details.push("[required]".to_string());
+        }
+        if let Some(default) = parameter.default() {
+            details.push(format!("[default: {}]", default));
+        }
+        if let Some(pattern) = parameter.pattern() {
+            details.push(format!("[pattern: {}]", pattern));
+        }
+        let name = format!("{} ({})", parameter.name(), parameter.options());
+        usage.push_str(format!("    {:<31} {}", name, details.join(" ")).trim_end());
+        usage.push('\n');
+    }
+    usage
+}
+
+pub fn config() -> String {
+    let usage = r#"
+Usage: templar config [-h | --help] show [--origin]
+       templar config [-h | --help] validate
+
+Commands:
+    show            Print each release and its templates
+    validate        Check the configuration files and report every problem as <file>:<line>:<column>
+
+Options:
+    -h, --help      Show this screen
+    --origin        Print the configuration files in the order they are layered and the file of each release
+"#;
+    usage.to_string()
+}
+
+pub fn init() -> String {
+    let usage = r#"
+Usage: templar init [-h | --help] [--force] [<path>]
+
+Options:
+    -h, --help      Show this screen
+    --force         Overwrite the configuration file if it already exists
+    <path>          The configuration file to create [default: ~/.templar.toml]
+"#;
+    usage.to_string()
+}
@@ -0,0 +1,311 @@
+//Configuration checks reported as `<file>:<line>:<column>: <problem>` diagnostics
+use regex::Regex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+use toml::Value as Toml;
+use toml::value::Table;
+
+use crate::date::{DateFormat, Timezone};
+use crate::git::{JiraPattern, PvtMarkers, TagPattern, WipMarker};
+use crate::parameter::{Parameter, TEXTS};
+use crate::release::{ArrayMerge, Context, InjectAt, Template};
+use crate::store::VersionSource;
+use crate::tracker::TrackerConf;
+use crate::version::Bump;
+
+type FieldCheck = fn(&Toml) -> Result<(), String>;
+
+//typed fields of a release and of its templates, each checked on its own so that every problem is reported at
+//its key rather than at the end of the table
+const RELEASE_FIELDS: &[(&str, FieldCheck)] = &[
+    ("schema", field::<PathBuf>),
+    ("jira-pattern", field::<JiraPattern>),
+    ("wip-marker", field::<WipMarker>),
+    ("tag-pattern", field::<TagPattern>),
+    ("bump", field::<Bump>),
+    ("pvt-markers", field::<PvtMarkers>),
+    ("main-pattern", field::<String>),
+    ("tracker", field::<TrackerConf>),
+    ("current-version-source", field::<VersionSource>),
+    ("tweet-template", field::<String>),
+    ("tweet-max-length", field::<usize>),
+    ("tweet-file", field::<PathBuf>),
+    ("interactive", field::<bool>),
+];
+
+const TEMPLATE_FIELDS: &[(&str, FieldCheck)] = &[
+    ("array-merge", field::<ArrayMerge>),
+    ("inject-at", field::<InjectAt>),
+    ("schema", field::<PathBuf>),
+    ("date-format", field::<DateFormat>),
+    ("timezone", field::<Timezone>),
+];
+
+#[derive(Deserialize)]
+struct RawConf {
+    #[serde(default)]
+    releases: Vec<RawRelease>,
+    #[serde(default)]
+    fragments: Table,
+}
+
+#[derive(Deserialize)]
+struct RawRelease {
+    name: Spanned<String>,
+    #[serde(default)]
+    templates: Vec<RawTemplate>,
+    #[serde(rename = "tweet-template")]
+    tweet_template: Option<Spanned<String>>,
+    #[serde(default)]
+    parameters: Vec<RawParameter>,
+    #[serde(default)]
+    vars: Table,
+}
+
+#[derive(Deserialize)]
+struct RawParameter {
+    name: Spanned<String>,
+    default: Option<Spanned<String>>,
+}
+
+#[derive(Deserialize)]
+struct RawTemplate {
+    id: Spanned<String>,
+    extends: Option<Spanned<String>>,
+    #[serde(default)]
+    include: Vec<Spanned<String>>,
+    content: Option<Toml>,
+}
+
+//the problems of every configuration file (path and content), then the templates extended and the fragments
+//included across the files in the order they are layered
+pub fn check(files: &[(PathBuf, String)]) -> Vec<String> {
+    let mut diagnostics: Vec<String> = vec![];
+    let mut layers: Vec<(&Path, &str, RawConf)> = vec![];
+    for (file, source) in files {
+        let parsed = toml::from_str::<RawConf>(source)
+            .and_then(|conf| toml::from_str::<Toml>(source).map(|value| (conf, value)));
+        match parsed {
+            Ok((conf, value)) => {
+                diagnostics.extend(check_file(file, source, &conf, &value));
+                layers.push((file, source, conf));
+            }
+            Err(err) => diagnostics.push(toml_error(file, &err)),
+        }
+    }
+    if layers.len() == files.len() {
+        diagnostics.extend(check_references(&layers));
+    }
+    diagnostics
+}
+
+fn check_file(file: &Path, source: &str, conf: &RawConf, value: &Toml) -> Vec<String> {
+    let diagnostic = |offset: usize, message: String| {
+        let (line, column) = line_column(source, offset);
+        format!("{}:{}:{}: {}", file.display(), line, column, message)
+    };
+    let release_tables = tables(value.get("releases"));
+    let mut diagnostics: Vec<String> = vec![];
+    let mut releases: HashMap<&str, usize> = HashMap::new();
+    for (index, release) in conf.releases.iter().enumerate() {
+        let name = release.name.get_ref().as_str();
+        let end = conf.releases.get(index + 1).map_or(source.len(), |r| r.name.start());
+        let release_table = release_tables.get(index);
+        for (key, problem) in release_table.map_or(vec![], |table| check_fields(table, RELEASE_FIELDS)) {
+            let offset = locate_key(source, "[[releases]]", 0, release.name.start(), &key);
+            diagnostics.push(diagnostic(offset, format!("Release '{}': {} at '{}'", name, problem, key)));
+        }
+        let vars: Vec<String> = release.vars.keys().cloned().collect();
+        for var in &vars {
+            let offset = locate(source, release.name.start(), end, var);
+            if Context::is_placeholder(var) {
+                diagnostics.push(diagnostic(offset, format!("Release '{}': variable '{}' shadows the placeholder '{{{}}}'",
+                                                            name, var, var)));
+            } else if var.is_empty() || !var.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+                diagnostics.push(diagnostic(offset, format!(
+                    "Release '{}': invalid variable name '{}', expected letters, digits, '-' or '_'", name, var)));
+            }
+        }
+        if let Some(first) = releases.insert(name, release.name.start()) {
+            diagnostics.push(diagnostic(release.name.start(), format!(
+                "Duplicate release '{}', first defined at line {}", name, line_column(source, first).0)));
+        }
+        if release.templates.is_empty() {
+            diagnostics.push(diagnostic(release.name.start(), format!("Release '{}' has no templates", name)));
+        }
+        if let Some(template) = &release.tweet_template {
+            let text = Toml::String(template.get_ref().to_owned());
+            let bound: Vec<String> = vars.iter().cloned().chain(Some("items".to_string())).collect();
+            for (path, _, problem) in Template::check(&text, "tweet-template", &bound) {
+                diagnostics.push(diagnostic(template.start(), format!("Release '{}': {} at '{}'", name, problem, path)));
+            }
+        }
+        let mut parameters: HashMap<&str, usize> = HashMap::new();
+        let parameter_tables = tables(release_table.and_then(|table| table.get("parameters")));
+        for (i, parameter) in release.parameters.iter().enumerate() {
+            let parameter_name = parameter.name.get_ref().as_str();
+            if let Some(Err(problem)) = parameter_tables.get(i).copied().map(field::<Parameter>) {
+                diagnostics.push(diagnostic(parameter.name.start(), format!("Release '{}': {}", name, problem)));
+            }
+            if let Some(first) = parameters.insert(parameter_name, parameter.name.start()) {
+                diagnostics.push(diagnostic(parameter.name.start(), format!(
+                    "Duplicate parameter '{}' in release '{}', first defined at line {}",
+                    parameter_name, name, line_column(source, first).0)));
+            }
+            if let Some(default) = parameter.default.as_ref().filter(|_| TEXTS.contains(&parameter_name)) {
+                let text = Toml::String(default.get_ref().to_owned());
+                let path = format!("parameters.{}.default", parameter_name);
+                for (path, _, problem) in Template::check(&text, &path, &vars) {
+                    diagnostics.push(diagnostic(default.start(), format!("Release '{}': {} at '{}'", name, problem, path)));
+                }
+            }
+        }
+        let template_tables = tables(release_table.and_then(|table| table.get("templates")));
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        for (i, template) in release.templates.iter().enumerate() {
+            let id = template.id.get_ref().as_str();
+            let problems = template_tables.get(i).map_or(vec![], |table| check_fields(table, TEMPLATE_FIELDS));
+            for (key, problem) in problems {
+                let offset = locate_key(source, "[[releases.templates]]", release.name.start(), template.id.start(),
+                                        &key);
+                diagnostics.push(diagnostic(offset, format!("Template '{}': {} at '{}'", id, problem, key)));
+            }
+            if let Some(first) = ids.insert(id, template.id.start()) {
+                diagnostics.push(diagnostic(template.id.start(), format!(
+                    "Duplicate template '{}' in release '{}', first defined at line {}",
+                    id, name, line_column(source, first).0)));
+            }
+            let end = release.templates.get(i + 1).map_or(end, |t| t.id.start());
+            for (path, text, problem) in template.content.iter().flat_map(|c| Template::check(c, "content", &vars)) {
+                let offset = locate(source, template.id.start(), end, &text);
+                diagnostics.push(diagnostic(offset, format!("Template '{}': {} at '{}'", id, problem, path)));
+            }
+        }
+    }
+    //fragments may be included by any release, so they may use the variables of any of them
+    let vars: Vec<String> = conf.releases.iter().flat_map(|release| release.vars.keys().cloned()).collect();
+    for (name, fragment) in &conf.fragments {
+        for (path, text, problem) in Template::check(fragment, &format!("fragments.{}", name), &vars) {
+            let offset = locate(source, 0, source.len(), &text);
+            diagnostics.push(diagnostic(offset, format!("Fragment '{}': {} at '{}'", name, problem, path)));
+        }
+    }
+    diagnostics
+}
+
+//templates extended and fragments included that do not exist once the files are layered, and cyclic inheritance,
+//reported at the extends or include value of the template
+fn check_references(layers: &[(&Path, &str, RawConf)]) -> Vec<String> {
+    let mut releases: Vec<(&str, &Path, &str, &RawRelease)> = vec![];
+    let mut fragments: Vec<&str> = vec![];
+    for (file, source, conf) in layers {
+        for release in &conf.releases {
+            let name = release.name.get_ref().as_str();
+            match releases.iter().position(|(other, ..)| other.eq(&name)) {
+                Some(index) => releases[index] = (name, file, source, release),
+                None => releases.push((name, file, source, release)),
+            }
+        }
+        fragments.extend(conf.fragments.keys().map(String::as_str));
+    }
+    let find = |key: &str| -> Option<&RawTemplate> {
+        let (release, id) = key.split_once('/')?;
+        releases.iter().find(|(name, ..)| name.eq(&release))
+            .and_then(|(.., raw)| raw.templates.iter().find(|template| template.id.get_ref().eq(id)))
+    };
+    //the extended template as "<release>/<id>", a template of the same release when the release is left out
+    let parent = |release: &str, template: &RawTemplate| template.extends.as_ref()
+        .map(|extends| match extends.get_ref() {
+            extends if extends.contains('/') => extends.to_owned(),
+            extends => format!("{}/{}", release, extends),
+        });
+    let mut diagnostics: Vec<String> = vec![];
+    for (name, file, source, release) in &releases {
+        let diagnostic = |offset: usize, message: String| {
+            let (line, column) = line_column(source, offset);
+            format!("{}:{}:{}: {}", file.display(), line, column, message)
+        };
+        for template in &release.templates {
+            let key = format!("{}/{}", name, template.id.get_ref());
+            if let (Some(extends), Some(parent_key)) = (&template.extends, parent(name, template)) {
+                if find(&parent_key).is_none() {
+                    diagnostics.push(diagnostic(extends.start(), format!(
+                        "Unknown template '{}' extended by '{}'", parent_key, key)));
+                }
+                //the ancestors up to a missing template, the top one or one met before, a cycle when it is this one
+                let mut chain = vec![key.to_owned()];
+                let mut next = Some(parent_key);
+                while let Some(current) = next.take() {
+                    if chain.contains(&current) {
+                        if current.eq(&key) {
+                            chain.push(current);
+                            diagnostics.push(diagnostic(extends.start(), format!(
+                                "Cyclic template inheritance: {}", chain.join(" -> "))));
+                        }
+                        break;
+                    }
+                    let release = current.split_once('/').map_or("", |(release, _)| release);
+                    next = find(&current).and_then(|template| parent(release, template));
+                    chain.push(current);
+                }
+            }
+            for include in template.include.iter().filter(|include| !fragments.contains(&include.get_ref().as_str())) {
+                diagnostics.push(diagnostic(include.start(), format!(
+                    "Unknown fragment '{}' included by '{}'", include.get_ref(), key)));
+            }
+        }
+    }
+    diagnostics
+}
+
+pub fn toml_error(file: &Path, err: &toml::de::Error) -> String {
+    let message = err.to_string();
+    match (err.line_col(), message.rfind(" at line ")) {
+        (Some((line, column)), Some(index)) =>
+            format!("{}:{}:{}: {}", file.display(), line + 1, column + 1, &message[..index]),
+        _ => format!("{}: {}", file.display(), message),
+    }
+}
+
+//the problems of the typed fields of the table as (key, problem)
+fn check_fields(table: &Toml, fields: &[(&str, FieldCheck)]) -> Vec<(String, String)> {
+    fields.iter().filter_map(|(key, check)| table.get(*key).and_then(|value| check(value).err())
+        .map(|problem| (key.to_string(), problem))).collect()
+}
+
+fn field<T: DeserializeOwned>(value: &Toml) -> Result<(), String> {
+    value.clone().try_into::<T>().map(|_| ()).map_err(|err| err.to_string())
+}
+
+//the tables of an array of tables
+fn tables(values: Option<&Toml>) -> Vec<&Toml> {
+    values.and_then(Toml::as_array).map_or(vec![], |values| values.iter().filter(|value| value.is_table()).collect())
+}
+
+//offset of the key within the table section of the header before the offset (after the start), or of the first
+//mention of the key after the offset when the table is written inline
+fn locate_key(source: &str, header: &str, start: usize, offset: usize, key: &str) -> usize {
+    let start = source.get(start..offset).and_then(|before| before.rfind(header)).map_or(offset, |index| start + index);
+    let end = source.get(start + 1..).and_then(|after| after.find("\n["))
+        .map_or(source.len(), |index| start + 1 + index);
+    let pattern = Regex::new(&format!(r#"(?m)^[ \t]*"?{}"?[ \t]*="#, regex::escape(key))).expect("key pattern");
+    match pattern.find(&source[start..end]) {
+        Some(found) => start + found.as_str().find(key).map_or(found.start(), |index| found.start() + index),
+        None => locate(source, start, end, key),
+    }
+}
+
+//offset of the text within the range, or the start of the range when it is written with escapes
+fn locate(source: &str, start: usize, end: usize, text: &str) -> usize {
+    source.get(start..end).and_then(|range| range.find(text)).map_or(start, |offset| start + offset)
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
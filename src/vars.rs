@@ -0,0 +1,69 @@
+//Custom variables of a release, declared with their defaults in `[releases.vars]` and available to the
+//templates as placeholders, e.g. {environment} or {build-number}
+use serde_json::{Map, Value as Json};
+use std::path::Path;
+
+use crate::release::Release;
+
+const ENV_PREFIX: &str = "TEMPLAR_VAR_";
+
+//the declared variables with the values of, in order of precedence, the `--var <name>=<value>` options, the vars
+//file, the TEMPLAR_VAR_<NAME> environment variables (name in upper case with '_' for '-') and the defaults, the
+//text values of the options and environment variables are converted to the type of the default
+pub fn variables(release: &Release, vars: &[String], file: Option<&Path>) -> Result<Map<String, Json>, String> {
+    let mut values = release.vars().to_owned();
+    for (name, default) in release.vars() {
+        let env_name = env_name(name);
+        if let Ok(value) = std::env::var(&env_name) {
+            values.insert(name.to_owned(), coerce(default, &value).map_err(|err| format!("{} {}", env_name, err))?);
+        }
+    }
+    if let Some(file) = file {
+        for (name, value) in read(file)? {
+            declared(release, &name).map_err(|err| format!("{} in vars file '{}'", err, file.display()))?;
+            values.insert(name, value);
+        }
+    }
+    for var in vars {
+        let (name, value) = var.split_once('=')
+            .ok_or(format!("Invalid variable '{}', expected --var <name>=<value>", var))?;
+        let value = coerce(declared(release, name)?, value).map_err(|err| format!("--var {} {}", name, err))?;
+        values.insert(name.to_string(), value);
+    }
+    Ok(values)
+}
+
+//the default of a declared variable
+fn declared<'a>(release: &'a Release, name: &str) -> Result<&'a Json, String> {
+    release.vars().get(name).ok_or_else(|| format!("Unknown variable '{}' of release '{}' (declared variables: {})",
+                                                    name, release.name, release.vars().keys().cloned().collect::<Vec<_>>().join(", ")))
+}
+
+//the text as a value of the type of the default: a number, a boolean, json for arrays and tables, or else text
+fn coerce(default: &Json, text: &str) -> Result<Json, String> {
+    let expected = match default {
+        Json::Number(_) => "a number",
+        Json::Bool(_) => "a boolean",
+        Json::Array(_) => "a json array",
+        Json::Object(_) => "a json object",
+        Json::String(_) | Json::Null => return Ok(Json::String(text.to_string())),
+    };
+    match serde_json::from_str::<Json>(text.trim()) {
+        Ok(value) if std::mem::discriminant(&value) == std::mem::discriminant(default) => Ok(value),
+        _ => Err(format!("value '{}' is not {} like the default {}", text, expected, default)),
+    }
+}
+
+fn env_name(name: &str) -> String {
+    format!("{}{}", ENV_PREFIX, name.to_uppercase().replace('-', "_"))
+}
+
+//a table of variable values, toml when the file has the .toml extension and json otherwise
+fn read(file: &Path) -> Result<Map<String, Json>, String> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|err| format!("Unable to read vars file '{}': {}", file.display(), err))?;
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => toml::from_str(&content).map_err(|err| err.to_string()),
+        _ => serde_json::from_str(&content).map_err(|err| err.to_string()),
+    }.map_err(|err| format!("Invalid vars file '{}': {}", file.display(), err))
+}
@@ -0,0 +1,151 @@
+//Versions of a release and how the next version is derived from the current one
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value as Json;
+use std::cmp::Ordering;
+use std::fmt;
+
+//major, minor or patch, or inferred from conventional commit messages: a breaking change ("BREAKING CHANGE"
+//or "<type>!:") bumps the major, a "feat" the minor and anything else the patch part
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Major,
+    Minor,
+    #[default]
+    Patch,
+    Conventional,
+}
+
+impl Bump {
+    pub fn parse(part: &str) -> Result<Bump, String> {
+        match part {
+            "major" => Ok(Bump::Major),
+            "minor" => Ok(Bump::Minor),
+            "patch" => Ok(Bump::Patch),
+            _ => Err(format!("Unknown version part '{}', expected major, minor or patch", part)),
+        }
+    }
+
+    //the part bumped by the commit messages for the conventional strategy
+    pub fn infer(self, messages: &[String]) -> Bump {
+        if self != Bump::Conventional {
+            return self;
+        }
+        let breaking = Regex::new(r"^\w+(\([^)]*\))?!:").expect("breaking change pattern");
+        let feature = Regex::new(r"^feat(\([^)]*\))?:").expect("feature pattern");
+        if messages.iter().any(|m| breaking.is_match(m) || m.contains("BREAKING CHANGE")) {
+            Bump::Major
+        } else if messages.iter().any(|m| feature.is_match(m)) {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+}
+
+//increments the part and resets the following ones and the pre-release, conventional bumps are expected to
+//be inferred first, a part that can not be incremented is an error
+pub fn bump(version: &Version, part: Bump) -> Result<Version, String> {
+    let position = match part {
+        Bump::Major => 0,
+        Bump::Minor => 1,
+        Bump::Patch | Bump::Conventional => 2,
+    };
+    let mut numbers = version.numbers.to_owned();
+    numbers.resize(numbers.len().max(position + 1), 0);
+    numbers[position] = numbers[position].checked_add(1)
+        .ok_or_else(|| format!("Unable to bump version '{}', its {} part is too large", version, ["major", "minor", "patch"][position]))?;
+    numbers.iter_mut().skip(position + 1).for_each(|number| *number = 0);
+    let text = numbers.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+    Ok(Version { text, numbers, pre: vec![], build: String::new() })
+}
+
+//SemVer (1.4.2-rc.1+build.5) or calendar (2026.10.1) version, missing minor and patch parts count as 0
+#[derive(Clone)]
+pub struct Version {
+    text: String,
+    numbers: Vec<u64>,
+    pre: Vec<String>,
+    build: String,
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Result<Version, String> {
+        let invalid = || format!("Invalid version '{}', expected <major>[.<minor>[.<patch>]][-<pre-release>][+<build>], \
+            e.g. 1.4.2, 1.5.0-rc.1 or 2026.10.1", text);
+        let (version, build) = text.split_once('+').unwrap_or((text, ""));
+        let (numbers, pre) = version.split_once('-').unwrap_or((version, ""));
+        let numbers = numbers.split('.').map(|n| n.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u64>, String>>()?;
+        let identifiers = |part: &str| part.split('.').all(|identifier| !identifier.is_empty()
+            && identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        if version.contains('-') && !identifiers(pre) || text.contains('+') && !identifiers(build) {
+            return Err(invalid());
+        }
+        let pre = if pre.is_empty() { vec![] } else { pre.split('.').map(str::to_string).collect() };
+        Ok(Version { text: text.to_string(), numbers, pre, build: build.to_string() })
+    }
+
+    //the major, minor, patch, pre (pre-release) or build component
+    pub fn component(&self, name: &str) -> Option<Json> {
+        let number = |index: usize| Some(Json::from(self.numbers.get(index).copied().unwrap_or(0)));
+        match name {
+            "major" => number(0),
+            "minor" => number(1),
+            "patch" => number(2),
+            "pre" => Some(Json::String(self.pre.join("."))),
+            "build" => Some(Json::String(self.build.to_owned())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+//printed as the version text so that the parsed context reads like the options given
+impl fmt::Debug for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.text)
+    }
+}
+
+//numbers are compared part by part, a pre-release precedes its release and the build is ignored
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        let length = self.numbers.len().max(other.numbers.len());
+        let numbers = |v: &Version| (0..length).map(|i| v.numbers.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+        numbers(self).cmp(&numbers(other)).then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.pre.iter().zip(&other.pre)
+                .map(|(a, b)| match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => a.cmp(b),
+                })
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| self.pre.len().cmp(&other.pre.len())),
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
@@ -294,18 +294,51 @@ fn validate_missing_wip_jiras_long_option_value() {
 
 // to view println: cargo test -- --nocapture
 #[test]
-fn conf_file_is_created_with_default_content_when_it_does_not_exists() {
+fn validate_missing_configuration() {
     let tmp_dir = TempDir::new().expect("temp_dir failed");
-    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    let mut cmd = templar_cmd(tmp_dir.path());
     cmd.arg("release").arg("test");
-    cmd.assert().success();
+    cmd.assert().failure()
+        .stderr(predicate::str::starts_with("error: No configuration found, run 'templar init' to create one"));
 
     let conf_file: PathBuf = [tmp_dir.path().to_str().unwrap(), ".templar.toml"].iter().collect();
-    assert!(conf_file.exists(), format!("file doesn't exist: {:?}", conf_file));
+    assert!(!conf_file.exists(), "file exists: {:?}", conf_file);
+}
 
+#[test]
+fn init_creates_example_configuration() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("init");
+    cmd.assert().success().stdout(predicate::str::starts_with("Created configuration file: "));
+
+    let conf_file: PathBuf = [tmp_dir.path().to_str().unwrap(), ".templar.toml"].iter().collect();
     let res = std::fs::read_to_string(conf_file);
     assert!(res.is_ok());
     assert!(res.unwrap().starts_with("# Templar Configuration"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("example")
+        .arg("-c").arg("1.0.0").arg("-n").arg("1.1.0")
+        .arg("-j").arg("JR-1").arg("JR-2");
+    cmd.assert().success().stdout(predicate::str::contains("\"title\":\"EXAMPLE 1.0.0 -> 1.1.0\"")
+        .and(predicate::str::contains("\"summary\":\"2 jiras released, wip: none\""))
+        .and(predicate::str::contains("\"url\":\"https://jira.example.com/browse/JR-2\""))
+    );
+}
+
+#[test]
+fn init_refuses_to_overwrite_configuration_without_force() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("init");
+    cmd.assert().failure().stderr(predicate::str::contains("already exists, use --force to overwrite it"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("init").arg("--force");
+    cmd.assert().success();
+    let conf_file: PathBuf = [tmp_dir.path().to_str().unwrap(), ".templar.toml"].iter().collect();
+    assert!(std::fs::read_to_string(conf_file).unwrap().contains("name = \"example\""));
 }
 
 #[test]
@@ -487,36 +520,1935 @@ fn prints_multiple_release_templates_without_variable_interpolation() {
     cmd.assert().success().stdout(predicate::str::contains("[{")
         .and(predicate::str::contains("\"region\":\"GLOBAL\""))
         .and(predicate::str::contains("\"tweet\":\"a default tweet\""))
-        .and(predicate::str::contains("}, {"))
+        .and(predicate::str::contains("},{"))
         .and(predicate::str::contains("\"region\":\"GLOBAL\""))
         .and(predicate::str::contains("\"tweet\":\"an external tweet\""))
         .and(predicate::str::contains("}]"))
     );
 }
 
-fn templar_cmd_with_default_conf(home_dir: &Path) -> Command {
+#[test]
+fn prints_release_template_with_placeholder_expressions() {
     let conf = indoc!(r#"
-        # Templar Configuration
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        title = "{name} {{{next-version}}}"
+        jira-list = "jiras: {jiras}"
+        tweet = "{tweet | \"n/a\"}"
+        wip = "{wip-jiras|\"none\"}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release")
+        .arg("-n").arg("1.22")
+        .arg("-t").arg("a tweet")
+        .arg("-j").arg("JR-1").arg("JR-2");
+    cmd.assert().success().stdout(predicate::str::contains("\"title\":\"a_release {1.22}\"")
+        .and(predicate::str::contains("\"jira-list\":\"jiras: JR-1, JR-2\""))
+        .and(predicate::str::contains("\"tweet\":\"a tweet\""))
+        .and(predicate::str::contains("\"wip\":\"none\""))
+    );
+}
 
+#[test]
+fn validate_unknown_placeholder() {
+    let conf = indoc!(r#"
         [[releases]]
-        name = "test"
+        name = "a_release"
+        [[releases.templates]]
+        id = "external"
+        [releases.templates.content]
+        business-verification = ["independent", "{unknown}"]
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:6:42: Template 'external': Unknown placeholder '{unknown}' at 'content.business-verification[1]'"));
+}
 
+#[test]
+fn validate_unclosed_placeholder() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
         [[releases.templates]]
         id = "default"
         [releases.templates.content]
-        region = "LDN"
-        nowVersion = "1"
-        nextVersion = "2"
-        tweet = "default tweet"
+        tweet = "{tweet"
     "#);
-    templar_cmd_with_conf(home_dir, conf)
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:6:10: Template 'default': Unclosed placeholder, missing '}' at 'content.tweet'"));
 }
 
-fn templar_cmd_with_conf(home_dir: &Path, conf: &'_ str) -> Command {
-    let tmp_dir = home_dir.to_str().unwrap();
-    let conf_file: PathBuf = [tmp_dir, ".templar.toml"].iter().collect();
-    std::fs::write(conf_file, conf).unwrap();
-    let mut cmd = Command::cargo_bin("templar").unwrap();
-    cmd.arg("--home").arg(tmp_dir);
-    cmd
-}
\ No newline at end of file
+#[test]
+fn prints_release_template_with_placeholder_filters() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        name = "{name | upper}"
+        tweet = "{tweet | truncate(4)}"
+        jiras-text = "{jiras | join(\"; \") | lower}"
+        after-next = "{next-version | semver_bump(\"minor\")}"
+        wip = "{wip-jiras | join | \"none\"}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release")
+        .arg("-n").arg("1.22.3")
+        .arg("-t").arg("a long tweet")
+        .arg("-j").arg("JR-1").arg("JR-2");
+    cmd.assert().success().stdout(predicate::str::contains("\"name\":\"A_RELEASE\"")
+        .and(predicate::str::contains("\"tweet\":\"a lo\""))
+        .and(predicate::str::contains("\"jiras-text\":\"jr-1; jr-2\""))
+        .and(predicate::str::contains("\"after-next\":\"1.23.0\""))
+        .and(predicate::str::contains("\"wip\":\"none\""))
+    );
+}
+
+#[test]
+fn validate_unknown_placeholder_filter() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tweet = "{tweet | shout}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:6:10: Template 'default': Unknown filter 'shout' (available filters: upper, lower, trim, truncate, join, first, last, semver_bump, length, number, bool, not) at 'content.tweet'"));
+}
+
+#[test]
+fn validate_placeholder_filter_arguments() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        next = "{next-version | semver_bump(\"huge\")}"
+        tweet = '{tweet | truncate("a")}'
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:4:6: Template 'default': Filter 'semver_bump': Unknown version part 'huge', expected major, minor or patch at 'content.next'")
+            .and(predicate::str::contains(
+                ".templar.toml:7:10: Template 'default': Filter 'truncate': Expected one numeric argument, e.g. truncate(280) at 'content.tweet'")));
+}
+
+#[test]
+fn prints_release_template_with_typed_placeholder_values() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        jiras = "{jiras}"
+        wip-jiras = "{wip-jiras}"
+        jira-count = "{jiras | length}"
+        build = "{next-version | number}"
+        has-wip = "{wip-jiras | bool}"
+        summary = "{jiras | length} jiras"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release")
+        .arg("-n").arg("42")
+        .arg("-j").arg("JR-1").arg("JR-2");
+    cmd.assert().success().stdout(predicate::str::contains("\"jiras\":[\"JR-1\",\"JR-2\"]")
+        .and(predicate::str::contains("\"wip-jiras\":[]"))
+        .and(predicate::str::contains("\"jira-count\":2"))
+        .and(predicate::str::contains("\"build\":42"))
+        .and(predicate::str::contains("\"has-wip\":false"))
+        .and(predicate::str::contains("\"summary\":\"2 jiras\""))
+    );
+}
+
+#[test]
+fn prints_release_template_with_conditional_and_loop_sections() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tickets = [
+            { _each = "{jiras}", _as = "jira", key = "{jira}", url = "https://jira/{jira}" },
+            { _each = "{wip-jiras}", key = "{item}", wip = true },
+            { _if = "{wip-jiras | not}", note = "no wip jiras" },
+        ]
+        [releases.templates.content.wip]
+        _if = "{wip-jiras}"
+        count = "{wip-jiras | length}"
+
+        [[releases.templates]]
+        id = "wip-only"
+        [releases.templates.content]
+        _if = "{wip-jiras}"
+        region = "GLOBAL"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release")
+        .arg("-j").arg("JR-1").arg("JR-2");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"tickets\":[{\"key\":\"JR-1\",\"url\":\"https://jira/JR-1\"},{\"key\":\"JR-2\",\"url\":\"https://jira/JR-2\"},{\"note\":\"no wip jiras\"}]")
+        .and(predicate::str::contains("\"wip\":").not())
+        .and(predicate::str::contains("GLOBAL").not())
+    );
+}
+
+#[test]
+fn prints_release_template_with_conditional_sections_enabled() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tickets = [
+            { _each = "{wip-jiras}", key = "{item}" },
+            { _if = "{wip-jiras | not}", note = "no wip jiras" },
+        ]
+        [releases.templates.content.wip]
+        _if = "{wip-jiras}"
+        count = "{wip-jiras | length}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release")
+        .arg("-w").arg("JR-3");
+    cmd.assert().success().stdout(predicate::str::contains("\"tickets\":[{\"key\":\"JR-3\"}]")
+        .and(predicate::str::contains("\"wip\":{\"count\":1}"))
+    );
+}
+
+#[test]
+fn validate_loop_section_outside_array() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content.tickets]
+        _each = "{jiras}"
+        key = "{item}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:6:1: Template 'default': '_each' is only allowed in array elements at 'content.tickets._each'"));
+}
+
+#[test]
+fn prints_release_template_extending_other_templates_and_fragments() {
+    let conf = indoc!(r#"
+        [fragments.global]
+        region = "GLOBAL"
+        tweet = "{tweet}"
+        business-verification = ["This release is independent"]
+
+        [[releases]]
+        name = "base"
+        [[releases.templates]]
+        id = "default"
+        include = ["global"]
+        [releases.templates.content]
+        release-date = "2020-01-01"
+        owner = { team = "core", channel = "releases" }
+
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        extends = "base/default"
+        array-merge = "append"
+        [releases.templates.content]
+        owner = { channel = "a-releases" }
+        business-verification = ["PVT: {pvt-line-range}"]
+
+        [[releases.templates]]
+        id = "external"
+        extends = "default"
+        [releases.templates.content]
+        business-verification = ["External"]
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-t").arg("a tweet").arg("-p").arg("1-2");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"business-verification\":[\"This release is independent\",\"PVT: 1-2\"]")
+        .and(predicate::str::contains("\"business-verification\":[\"External\"]"))
+        .and(predicate::str::contains("\"owner\":{\"team\":\"core\",\"channel\":\"a-releases\"}"))
+        .and(predicate::str::contains("\"region\":\"GLOBAL\""))
+        .and(predicate::str::contains("\"release-date\":\"2020-01-01\""))
+        .and(predicate::str::contains("\"tweet\":\"a tweet\""))
+    );
+}
+
+#[test]
+fn validate_cyclic_template_inheritance() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        extends = "a_release/external"
+        [[releases.templates]]
+        id = "external"
+        extends = "default"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(
+            ".templar.toml:5:11: Cyclic template inheritance: a_release/default -> a_release/external -> a_release/default"));
+}
+
+#[test]
+fn validate_unknown_fragment() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        include = ["missing"]
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(".templar.toml:5:12: Unknown fragment 'missing' included by 'a_release/default'"));
+}
+
+#[test]
+fn layers_system_user_project_and_explicit_configuration() {
+    let release = |name: &str, region: &str| indoc!(r#"
+        [[releases]]
+        name = "NAME"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        region = "REGION"
+    "#).replace("NAME", name).replace("REGION", region);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let project_dir = tmp_dir.path().join("project");
+    let current_dir = project_dir.join("src");
+    std::fs::create_dir_all(&current_dir).unwrap();
+    std::fs::write(tmp_dir.path().join("system.toml"), release("system", "SYSTEM") + &release("shared", "SYSTEM")).unwrap();
+    std::fs::write(project_dir.join(".templar.toml"), release("project", "PROJECT") + &release("shared", "PROJECT")).unwrap();
+    std::fs::write(tmp_dir.path().join("explicit.toml"), release("project", "EXPLICIT")).unwrap();
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &release("user", "USER"));
+    cmd.current_dir(&current_dir).arg("--config").arg(tmp_dir.path().join("explicit.toml"));
+    cmd.arg("config").arg("show");
+    cmd.assert().success().stdout(predicate::str::similar(
+        "system: default\nshared: default\nuser: default\nproject: default\n"));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &release("user", "USER"));
+    cmd.current_dir(&current_dir).arg("--config").arg(tmp_dir.path().join("explicit.toml"));
+    cmd.arg("release").arg("shared");
+    cmd.assert().success().stdout(predicate::str::contains("\"region\":\"PROJECT\""));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &release("user", "USER"));
+    cmd.current_dir(&current_dir).arg("--config").arg(tmp_dir.path().join("explicit.toml"));
+    cmd.arg("release").arg("project");
+    cmd.assert().success().stdout(predicate::str::contains("\"region\":\"EXPLICIT\""));
+}
+
+#[test]
+fn shows_configuration_origin_of_releases() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [[releases.templates]]
+        id = "external"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("show").arg("--origin");
+    let conf_file = tmp_dir.path().canonicalize().unwrap().join(".templar.toml");
+    cmd.assert().success().stdout(predicate::str::similar(format!(
+        "# {}\na_release: default, external ({})\n", conf_file.display(), conf_file.display())));
+}
+
+#[test]
+fn validate_missing_explicit_configuration() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("--config").arg("missing.toml").arg("release").arg("test");
+    cmd.assert().failure()
+        .stderr(predicate::str::starts_with("error: Configuration file not found: missing.toml"));
+}
+
+#[test]
+fn validates_configuration() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("config").arg("validate");
+    cmd.assert().success().stdout(predicate::str::starts_with("ok: ").and(predicate::str::contains(".templar.toml")));
+}
+
+#[test]
+fn validate_configuration_reports_every_problem_with_location() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tweet = "{tweet | shout}"
+
+        [[releases]]
+        name = "a_release"
+        templates = []
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(".templar.toml:6:6: Duplicate template 'default' in release 'a_release', first defined at line 4")
+            .and(predicate::str::contains(".templar.toml:8:10: Template 'default': Unknown filter 'shout'"))
+            .and(predicate::str::contains(".templar.toml:11:8: Duplicate release 'a_release', first defined at line 2"))
+            .and(predicate::str::contains(".templar.toml:11:8: Release 'a_release' has no templates"))
+        );
+}
+
+#[test]
+fn validate_configuration_reports_typed_fields_and_references_at_their_key() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        jira-pattern = "ABC-("
+        tweet-max-length = "long"
+        [[releases.templates]]
+        id = "default"
+        inject-at = "middle"
+        timezone = "Mars/Base"
+        extends = "missing"
+        [[releases.templates]]
+        id = "external"
+        inject-at = "nowhere"
+        include = ["absent"]
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains(".templar.toml:3:1: Release 'a_release': invalid jira-pattern 'ABC-('")
+            .and(predicate::str::contains(
+                ".templar.toml:4:1: Release 'a_release': invalid type: string \"long\", expected usize at 'tweet-max-length'"))
+            .and(predicate::str::contains(
+                ".templar.toml:7:1: Template 'default': invalid inject-at 'middle', expected start, end, before:<key> or after:<key> at 'inject-at'"))
+            .and(predicate::str::contains(
+                ".templar.toml:8:1: Template 'default': unknown timezone 'Mars/Base', expected a name such as UTC or Europe/London at 'timezone'"))
+            .and(predicate::str::contains(".templar.toml:9:11: Unknown template 'a_release/missing' extended by 'a_release/default'"))
+            .and(predicate::str::contains(
+                ".templar.toml:12:1: Template 'external': invalid inject-at 'nowhere', expected start, end, before:<key> or after:<key> at 'inject-at'"))
+            .and(predicate::str::contains(".templar.toml:13:12: Unknown fragment 'absent' included by 'a_release/external'"))
+        );
+
+    let base = indoc!(r#"
+        [fragments.global]
+        region = "GLOBAL"
+        [[releases]]
+        name = "base"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    std::fs::write(tmp_dir.path().join("base.toml"), base).unwrap();
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        extends = "base/default"
+        include = ["global"]
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("--config").arg(tmp_dir.path().join("base.toml")).arg("config").arg("validate");
+    cmd.assert().success();
+}
+
+#[test]
+fn validate_configuration_syntax_with_location() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure().stderr(predicate::str::contains(".templar.toml:2:18: newline in string found"));
+}
+
+#[test]
+fn writes_release_json_to_output_file() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let output = tmp_dir.path().join("release.json");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-o").arg(&output);
+    cmd.assert().success().stdout(predicate::str::contains("Created: "));
+    let json = std::fs::read_to_string(&output).unwrap();
+    assert!(json.starts_with("[{") && json.contains("\"region\":\"LDN\""), "unexpected json: {}", json);
+
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--output").arg(&output);
+    cmd.assert().failure().stderr(predicate::str::contains("already exists, use --force to overwrite it"));
+
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--output").arg(&output).arg("--force");
+    cmd.assert().success();
+    assert!(std::fs::read_to_string(&output).unwrap().contains("\"tweet\":\"default tweet\""));
+}
+
+#[test]
+fn writes_release_json_split_per_template() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        region = "GLOBAL"
+        [[releases.templates]]
+        id = "external"
+        [releases.templates.content]
+        region = "EXTERNAL"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let output = tmp_dir.path().join("out");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-o").arg(&output).arg("--split");
+    cmd.assert().success();
+    assert!(std::fs::read_to_string(output.join("default.json")).unwrap().contains("\"region\":\"GLOBAL\""));
+    assert!(std::fs::read_to_string(output.join("external.json")).unwrap().contains("\"region\":\"EXTERNAL\""));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-o").arg(&output).arg("--split");
+    cmd.assert().failure().stderr(predicate::str::contains("default.json' already exists"));
+}
+
+#[test]
+fn prints_release_json_in_pretty_format() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        region = "GLOBAL"
+        release-date = "2020-01-01"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--format").arg("pretty");
+    cmd.assert().success().stdout(predicate::str::similar(indoc!(r#"
+        [
+          {
+            "region": "GLOBAL",
+            "release-date": "2020-01-01",
+            "jiras": [],
+            "wip-jiras": []
+          }
+        ]
+    "#)));
+}
+
+#[test]
+fn prints_release_json_in_canonical_format() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        count = 2.0
+        ratio = 0.5
+        release-date = "2020-01-01"
+        [[releases.templates]]
+        id = "external"
+        [releases.templates.content]
+        release-date = "2020-01-01"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--format").arg("canonical");
+    cmd.assert().success().stdout(predicate::str::similar(concat!(
+        "[{\"count\":2,\"jiras\":[],\"ratio\":0.5,\"release-date\":\"2020-01-01\",\"wip-jiras\":[],\"zone\":\"GLOBAL\"},",
+        "{\"jiras\":[],\"release-date\":\"2020-01-01\",\"wip-jiras\":[]}]\n")));
+}
+
+#[test]
+fn validate_unknown_output_format() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--format").arg("xml");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains("error: Unknown format 'xml' (available formats: compact, pretty, canonical, ndjson, yaml, toml)"));
+}
+
+#[test]
+fn prints_release_in_ndjson_yaml_and_toml_formats_keeping_key_order() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        release-date = "2020-01-01"
+        jiras = "{jiras}"
+        owner = { team = "core", channel = "releases" }
+        [[releases.templates]]
+        id = "external"
+        [releases.templates.content]
+        zone = "EXTERNAL"
+        release-date = "2020-01-01"
+        jiras = "{jiras}"
+        wip-jiras = "{wip-jiras}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("--format").arg("ndjson");
+    cmd.assert().success().stdout(predicate::str::similar(concat!(
+        "{\"zone\":\"GLOBAL\",\"release-date\":\"2020-01-01\",\"jiras\":[\"JR-1\"],",
+        "\"owner\":{\"team\":\"core\",\"channel\":\"releases\"},\"wip-jiras\":[]}\n",
+        "{\"zone\":\"EXTERNAL\",\"release-date\":\"2020-01-01\",\"jiras\":[\"JR-1\"],\"wip-jiras\":[]}\n")));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("--format").arg("yaml");
+    cmd.assert().success().stdout(predicate::str::similar(indoc!(r#"
+        ---
+        - zone: GLOBAL
+          release-date: 2020-01-01
+          jiras:
+            - JR-1
+          owner:
+            team: core
+            channel: releases
+          wip-jiras: []
+        - zone: EXTERNAL
+          release-date: 2020-01-01
+          jiras:
+            - JR-1
+          wip-jiras: []
+    "#)));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("--format").arg("toml");
+    cmd.assert().success().stdout(predicate::str::similar(indoc!(r#"
+        [[templates]]
+        zone = "GLOBAL"
+        release-date = "2020-01-01"
+        jiras = ["JR-1"]
+        wip-jiras = []
+
+        [templates.owner]
+        team = "core"
+        channel = "releases"
+
+        [[templates]]
+        zone = "EXTERNAL"
+        release-date = "2020-01-01"
+        jiras = ["JR-1"]
+        wip-jiras = []
+    "#)));
+
+    let conf = conf.replace("team = \"core\"", "team = \"{wip-jiras | first}\"");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("--format").arg("toml");
+    cmd.assert().failure().stdout(predicate::str::is_empty()).stderr(predicate::str::contains(
+        "error: Template 'default': null value at 'owner.team' can not be written as toml"));
+}
+
+#[test]
+fn prints_release_template_with_injected_keys_at_configured_position() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "end"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        wip-jiras = []
+        tweet = "a tweet"
+        [[releases.templates]]
+        id = "start"
+        inject-at = "start"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        tweet = "a tweet"
+        [[releases.templates]]
+        id = "after"
+        inject-at = "after:zone"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        release-date = "2020-01-01"
+        tweet = "a tweet"
+        [[releases.templates]]
+        id = "before"
+        inject-at = "before:tweet"
+        [releases.templates.content]
+        zone = "GLOBAL"
+        release-date = "2020-01-01"
+        tweet = "a tweet"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--format").arg("ndjson");
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    cmd.assert().success().stdout(predicate::str::contains(format!(
+        "{{\"zone\":\"GLOBAL\",\"wip-jiras\":[],\"tweet\":\"a tweet\",\"jiras\":[],\"release-date\":\"{}", date))
+        .and(predicate::str::contains(format!(
+            "{{\"jiras\":[],\"wip-jiras\":[],\"release-date\":\"{}", date)))
+        .and(predicate::str::contains(
+            "{\"zone\":\"GLOBAL\",\"jiras\":[],\"wip-jiras\":[],\"release-date\":\"2020-01-01\",\"tweet\":\"a tweet\"}"))
+        .and(predicate::str::contains(
+            "{\"zone\":\"GLOBAL\",\"release-date\":\"2020-01-01\",\"jiras\":[],\"wip-jiras\":[],\"tweet\":\"a tweet\"}"))
+    );
+}
+
+#[test]
+fn validate_injected_keys_position() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        inject-at = "after:missing"
+        [releases.templates.content]
+        zone = "GLOBAL"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure()
+        .stderr(predicate::str::contains("error: Template 'default': inject-at key 'missing' not found"));
+}
+
+#[test]
+fn prints_release_template_matching_its_schema() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        schema = "schemas/release.json"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        owner = "team"
+        jiras = []
+    "#);
+    let schema = indoc!(r#"
+        {
+          "type": "object",
+          "required": ["owner", "jiras"],
+          "properties": {
+            "owner": { "type": "string" },
+            "jiras": { "type": "array", "items": { "type": "string", "pattern": "^JR-[0-9]+$" } }
+          }
+        }
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    std::fs::create_dir(tmp_dir.path().join("schemas")).unwrap();
+    std::fs::write(tmp_dir.path().join("schemas/release.json"), schema).unwrap();
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"owner\":\"team\",\"jiras\":[\"JR-1\"]"));
+}
+
+#[test]
+fn validate_release_templates_against_their_schema() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        schema = "release.json"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        jiras = []
+        [[releases.templates]]
+        id = "strict"
+        schema = "strict.json"
+        [releases.templates.content]
+        owner = 1
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    std::fs::write(tmp_dir.path().join("release.json"),
+                   r#"{"required": ["owner"], "properties": {"jiras": {"items": {"pattern": "^JR-"}}}}"#).unwrap();
+    std::fs::write(tmp_dir.path().join("strict.json"),
+                   r#"{"properties": {"owner": {"type": "string"}}, "additionalProperties": false}"#).unwrap();
+    let output = tmp_dir.path().join("out.json");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("BUG-1").arg("-o").arg(&output);
+    cmd.assert().failure().stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("Template 'default' does not match schema at #: missing required property 'owner'")
+            .and(predicate::str::contains(
+                "Template 'default' does not match schema at #/jiras/0: \"BUG-1\" does not match pattern '^JR-'"))
+            .and(predicate::str::contains(
+                "Template 'strict' does not match schema at #/owner: expected string but found number"))
+            .and(predicate::str::contains(
+                "Template 'strict' does not match schema at #/jiras: additional property is not allowed")));
+    assert!(!output.exists());
+
+    std::fs::write(tmp_dir.path().join("release.json"), r#"{"$schema": "http://json-schema.org/draft-07/schema#",
+        "properties": {"jiras": {"if": {"required": ["x"]}, "then": {"required": ["zzz"]}}}}"#).unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-o").arg(&output);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "release.json': unsupported keyword 'if' at #/properties/jiras/if (supported keywords: type, enum, "));
+    assert!(!output.exists());
+}
+
+#[test]
+fn prints_release_date_in_template_timezone_and_format() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "utc"
+        [[releases.templates]]
+        id = "london"
+        date-format = "%d/%m/%Y %H:%M"
+        timezone = "Europe/London"
+        [[releases.templates]]
+        id = "fixed"
+        timezone = "Asia/Tokyo"
+        [releases.templates.content]
+        release-date = "2020-01-01"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--release-date").arg("2020-07-01T10:00:00Z").arg("--format").arg("ndjson");
+    cmd.assert().success().stdout(concat!(
+        "{\"jiras\":[],\"wip-jiras\":[],\"release-date\":\"2020-07-01 10:00:00\"}\n",
+        "{\"jiras\":[],\"wip-jiras\":[],\"release-date\":\"01/07/2020 11:00\"}\n",
+        "{\"release-date\":\"2020-01-01\",\"jiras\":[],\"wip-jiras\":[]}\n"));
+}
+
+#[test]
+fn prints_relative_release_date() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        date-format = "%Y-%m-%d %H:%M"
+        timezone = "America/New_York"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--release-date").arg("+2d 18:00");
+    let date = (Utc::now().with_timezone(&chrono_tz::America::New_York) + chrono::Duration::days(2))
+        .format("%Y-%m-%d 18:00").to_string();
+    cmd.assert().success().stdout(predicate::str::contains(format!("\"release-date\":\"{}\"", date)));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--release-date").arg("today 09:30");
+    let date = Utc::now().with_timezone(&chrono_tz::America::New_York).format("%Y-%m-%d 09:30").to_string();
+    cmd.assert().success().stdout(predicate::str::contains(format!("\"release-date\":\"{}\"", date)));
+}
+
+#[test]
+fn validate_release_date_and_timezone() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--release-date").arg("someday");
+    cmd.assert().failure().stderr(predicate::str::contains("error: Invalid release date 'someday'"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--release-date").arg("+99999999999999999d");
+    cmd.assert().failure().code(1).stderr(predicate::str::contains("error: Invalid release date '+99999999999999999d'"));
+
+    for date in ["+-5d", "++5d", "+ 5d", "+5.0d"] {
+        let mut cmd = templar_cmd(tmp_dir.path());
+        cmd.arg("release").arg("test").arg("--release-date").arg(date);
+        cmd.assert().failure().stderr(predicate::str::contains(format!("error: Invalid release date '{}'", date)));
+    }
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--release-date").arg("+9999999999d");
+    cmd.assert().failure().code(1).stderr(predicate::str::contains("does not exist"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        timezone = "Mars/Olympus"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains("unknown timezone 'Mars/Olympus'"));
+}
+
+#[test]
+fn populates_jiras_from_git_commits_since_latest_tag() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "JR-1 initial import");
+    git(tmp_dir.path(), &["tag", "v1"]);
+    commit(tmp_dir.path(), "JR-2 add feature");
+    commit(tmp_dir.path(), "WIP JR-3 partial work");
+    commit(tmp_dir.path(), "JR-4 fix, relates to JR-2");
+    commit(tmp_dir.path(), "WIP: JR-4 follow up");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-j").arg("JR-9").arg("--jiras-from-git");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"jiras\":[\"JR-9\",\"JR-2\",\"JR-4\"],\"wip-jiras\":[\"JR-3\"]"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--jiras-from-git").arg("HEAD~2..HEAD");
+    cmd.assert().success().stdout(predicate::str::contains("\"jiras\":[\"JR-4\",\"JR-2\"],\"wip-jiras\":[]"));
+}
+
+#[test]
+fn populates_jiras_from_git_commits_with_release_pattern_and_wip_marker() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        jira-pattern = "ABC-\\d+"
+        wip-marker = "DRAFT"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "ABC-1 and JR-1 shipped");
+    commit(tmp_dir.path(), "DRAFT ABC-2 in progress");
+    commit(tmp_dir.path(), "WIP ABC-3 done (WIPE is not the marker)");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--jiras-from-git");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"jiras\":[\"ABC-1\",\"ABC-3\"],\"wip-jiras\":[\"ABC-2\"]"));
+}
+
+#[test]
+fn validate_jiras_from_git() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "JR-1 initial import");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--jiras-from-git").arg("missing..HEAD");
+    cmd.assert().failure().stderr(predicate::str::contains("error: git log --reverse --format=%B%x00 missing..HEAD failed"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        jira-pattern = "ABC-("
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains("invalid jira-pattern 'ABC-('"));
+}
+
+#[test]
+fn derives_versions_from_git_tags() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tag-pattern = "myapp-v{version}"
+        bump = "minor"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        versions = "{now-version} -> {next-version}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "JR-1 initial import");
+    git(tmp_dir.path(), &["tag", "myapp-v1.2.0"]);
+    git(tmp_dir.path(), &["tag", "other-v9.0.0"]);
+    commit(tmp_dir.path(), "JR-2 add feature");
+    git(tmp_dir.path(), &["tag", "myapp-v1.10.0"]);
+    git(tmp_dir.path(), &["tag", "v5"]);
+    commit(tmp_dir.path(), "JR-3 fix");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--jiras-from-git");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"1.10.0 -> 1.11.0\"")
+        .and(predicate::str::contains("\"jiras\":[\"JR-3\"]")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-n").arg("3.0.0");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"1.10.0 -> 3.0.0\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-c").arg("2.0.0");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"2.0.0 -> 2.1.0\""));
+
+    let no_repo_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(no_repo_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-c").arg("2.0.0");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"2.0.0 -> 2.1.0\""));
+}
+
+#[test]
+fn derives_next_version_from_conventional_commits() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tag-pattern = "v{version}"
+        bump = "conventional"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        versions = "{now-version} -> {next-version}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "feat: initial import");
+    git(tmp_dir.path(), &["tag", "v1.0.0"]);
+    commit(tmp_dir.path(), "fix: typo");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"1.0.0 -> 1.0.1\""));
+
+    commit(tmp_dir.path(), "feat(ui): add button");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"1.0.0 -> 1.1.0\""));
+
+    commit(tmp_dir.path(), "refactor!: drop the legacy api");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::contains("\"versions\":\"1.0.0 -> 2.0.0\""));
+}
+
+#[test]
+fn validate_versions_from_git_tags() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tag-pattern = "myapp-v{version}"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "initial import");
+    git(tmp_dir.path(), &["tag", "v1.0.0"]);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: No tag matching tag-pattern 'myapp-v{version}' found, use --current <version>"));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf.replace("myapp-v{version}", "myapp"));
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid tag-pattern 'myapp', expected one {version} placeholder"));
+}
+
+#[test]
+fn prints_release_template_with_version_components() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        title = "v{next-version.major}.{next-version.minor} after {now-version}"
+        major = "{next-version.major}"
+        patch = "{current-version.patch}"
+        pre = "{next-version.pre | \"none\"}"
+        build = "{next-version.build}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-c").arg("1.4").arg("-n").arg("2.0.0-rc.1+build.5");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"title\":\"v2.0 after 1.4\",\"major\":2,\"patch\":0,\"pre\":\"rc.1\",\"build\":\"build.5\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-c").arg("2026.10.1");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"title\":\"v2026.10 after 2026.10.1\",\"major\":2026,\"patch\":1,\"pre\":\"none\",\"build\":\"\""));
+}
+
+#[test]
+fn validate_release_versions() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-c").arg("2.0").arg("-n").arg("1.9");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Next version '1.9' must be greater than current version '2.0'"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-c").arg("2.0.0").arg("-n").arg("2.0.0-rc.1");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Next version '2.0.0-rc.1' must be greater than current version '2.0.0'"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-c").arg("2.0.0-rc.2").arg("-n").arg("2.0.0").arg("--parse");
+    cmd.assert().success();
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-c").arg("2.x");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Invalid version '2.x', expected <major>[.<minor>[.<patch>]][-<pre-release>][+<build>]"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-c").arg("1.0.18446744073709551615");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Unable to bump version '1.0.18446744073709551615', its patch part is too large"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        size = "{next-version.size}"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Template 'default': Unknown placeholder '{next-version.size}' at 'content.size'"));
+}
+
+#[test]
+fn finds_pvt_line_range_and_main_class_in_git_repository() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        pvt-markers = ["// PVT-START", "// PVT-END"]
+        main-pattern = "public static void main"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        pvt = "{pvt-file}#L{pvt-line-range}"
+        main = "{main-class}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit_file(tmp_dir.path(), "src/main/java/com/example/App.java", indoc!("
+        package com.example;
+
+        public class App {
+            public static void main(String[] args) {}
+        }
+    "));
+    commit_file(tmp_dir.path(), "src/test/Pvt.java", indoc!("
+        class Pvt {
+            // PVT-START
+            check();
+            verify();
+            // PVT-END
+        }
+    "));
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"pvt\":\"src/test/Pvt.java#L3-4\",\"main\":\"com.example.App\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-p").arg("7-8").arg("--main-class").arg("com.example.Other");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"pvt\":\"src/test/Pvt.java#L7-8\",\"main\":\"com.example.Other\""));
+}
+
+#[test]
+fn validate_pvt_markers_and_main_class_in_git_repository() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        pvt-markers = ["// PVT-START", "// PVT-END"]
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit_file(tmp_dir.path(), "src/Pvt.java", "// PVT-START\ncheck();\n");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: PVT marker '// PVT-END' not found in the repository at HEAD"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-p").arg("1-2");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: PVT marker '// PVT-END' not found in the repository at HEAD"));
+
+    commit_file(tmp_dir.path(), "src/Other.java", "// PVT-END\n");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: Unbalanced PVT markers: '// PVT-START' at src/Pvt.java:1 and '// PVT-END' at src/Other.java:1"));
+
+    commit_file(tmp_dir.path(), "src/Pvt.java", "// PVT-START\ncheck();\n// PVT-START\n");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: PVT marker '// PVT-START' found more than once: src/Pvt.java:1, src/Pvt.java:3"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        main-pattern = "PVT-(START|END)"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: Main entry matching main-pattern 'PVT-(START|END)' found more than once: src/Other.java, src/Pvt.java"));
+
+    let conf = conf.replace("main-pattern = \"PVT-(START|END)\"", "pvt-markers = [\"// PVT-START\"]");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid pvt-markers [\"// PVT-START\"], expected two different markers"));
+}
+
+#[test]
+fn checks_release_jiras_against_pvt_code_and_commit_messages() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        pvt-markers = ["// PVT-START", "// PVT-END"]
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit_file(tmp_dir.path(), "src/Pvt.java", indoc!(r#"
+        class Pvt {
+            // PVT-START
+            check("JR-1");
+            check("JR-5");
+            // PVT-END
+        }
+    "#));
+    commit(tmp_dir.path(), "JR-1 feature");
+    commit(tmp_dir.path(), "JR-2 fix");
+    commit(tmp_dir.path(), "JR-7 unrelated");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-c").arg("1.0.0").arg("-n").arg("1.1.0").arg("-t").arg("a tweet")
+        .arg("-j").arg("JR-1").arg("JR-2").arg("-w").arg("JR-3").arg("--check");
+    let warnings = concat!(
+        "warning: Jira 'JR-2' is not referenced by the PVT code at src/Pvt.java:3-4\n",
+        "warning: Jira 'JR-3' is not referenced by the PVT code at src/Pvt.java:3-4 nor by the commit messages\n",
+        "warning: Jira 'JR-5' referenced by the PVT code at src/Pvt.java:3-4 is not part of the release\n",
+        "warning: Jira 'JR-7' referenced by the commit messages is not part of the release\n");
+    cmd.assert().success().stdout(predicate::str::is_empty()).stderr(warnings);
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-c").arg("1.0.0").arg("-n").arg("1.1.0").arg("-t").arg("a tweet")
+        .arg("-j").arg("JR-1").arg("JR-2").arg("-w").arg("JR-3").arg("--check").arg("--strict");
+    cmd.assert().failure().stderr(predicate::str::starts_with(warnings)
+        .and(predicate::str::contains("error: 4 check warning(s) with --strict")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("JR-5")
+        .arg("--jiras-from-git").arg("HEAD..HEAD").arg("--check").arg("--strict");
+    cmd.assert().success().stdout("ok: 2 jiras match the PVT code and the commit messages\n");
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("-p").arg("3-3")
+        .arg("--jiras-from-git").arg("HEAD..HEAD").arg("--check").arg("--strict");
+    cmd.assert().success().stdout("ok: 1 jiras match the PVT code and the commit messages\n");
+}
+
+#[test]
+fn validate_release_check_options() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "JR-1 feature");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--check");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: The --check option requires the PVT file, configure the release pvt-markers or use --pvt-file"));
+
+    commit_file(tmp_dir.path(), "Pvt.java", "check(\"JR-1\");\n");
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("-j").arg("JR-1").arg("-p").arg("1-1").arg("--pvt-file").arg("Pvt.java")
+        .arg("--jiras-from-git").arg("HEAD..HEAD").arg("--check");
+    cmd.assert().success().stdout("ok: 1 jiras match the PVT code and the commit messages\n");
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--strict");
+    cmd.assert().failure().stderr(predicate::str::contains("error: The --strict option requires --check"));
+}
+
+#[test]
+fn prints_release_template_with_jira_metadata_from_issue_files() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tracker = { type = "dir", path = "issues" }
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        summary = "{jira.JR-1.summary}"
+        status = "{jira.JR-3.status}"
+        fixes = "{jira.JR-1.fix-versions}"
+        summaries = "{jira-summaries}"
+        tweet = "{tweet}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    std::fs::create_dir(tmp_dir.path().join("issues")).unwrap();
+    write_issue(tmp_dir.path(), "JR-1", "Add login", "Done", &["1.2.0"]);
+    write_issue(tmp_dir.path(), "JR-2", "Fix crash", "Done", &[]);
+    write_issue(tmp_dir.path(), "JR-3", "Rework search", "In Progress", &[]);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("JR-2").arg("-w").arg("JR-3");
+    cmd.assert().success().stdout(predicate::str::starts_with(concat!(
+        "[{\"summary\":\"Add login\",\"status\":\"In Progress\",\"fixes\":[\"1.2.0\"],",
+        "\"summaries\":[\"Add login\",\"Fix crash\"],\"tweet\":\"Add login; Fix crash\"")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("JR-4");
+    cmd.assert().failure().stderr(predicate::str::contains("error: Jira 'JR-4': unable to read '")
+        .and(predicate::str::contains("JR-4.json'")));
+}
+
+#[test]
+fn prints_release_template_with_jira_metadata_from_jira_rest_api() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let mut requests: Vec<String> = vec![];
+        for _ in 0..2 {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut buffer = [0; 1024];
+            while !request.contains("\r\n\r\n") {
+                let length = std::io::Read::read(&mut stream, &mut buffer).unwrap();
+                request.push_str(&String::from_utf8_lossy(&buffer[..length]));
+            }
+            let (status, body) = if request.contains("Authorization: Bearer secret") {
+                ("200 OK", r#"{"key":"JR-1","fields":{"summary":"Add login","status":{"name":"Done"},"fixVersions":[]}}"#)
+            } else {
+                ("401 Unauthorized", "{}")
+            };
+            let response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                                   status, body.len(), body);
+            std::io::Write::write_all(&mut stream, response.as_bytes()).unwrap();
+            requests.push(request.lines().next().unwrap_or_default().to_string());
+        }
+        requests
+    });
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tracker = { type = "jira", url = "URL/", token-env = "TEMPLAR_TEST_JIRA_TOKEN" }
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        summary = "{jira.JR-1.summary} ({jira.JR-1.status})"
+    "#).replace("URL", &url);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf);
+    cmd.env("TEMPLAR_TEST_JIRA_TOKEN", "secret").arg("release").arg("a_release").arg("-j").arg("JR-1");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"summary\":\"Add login (Done)\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1");
+    cmd.assert().failure().stderr(predicate::str::contains(format!(
+        "error: Jira 'JR-1': request to {}/rest/api/2/issue/JR-1?fields=summary,description,status,fixVersions failed", url))
+        .and(predicate::str::contains("401")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.env("TEMPLAR_TEST_JIRA_TOKEN", "secret").arg("release").arg("a_release").arg("-j").arg("JR-1/../../myself");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: Invalid jira 'JR-1/../../myself', expected a key matching jira-pattern"));
+    assert_eq!(server.join().unwrap(), vec![
+        "GET /rest/api/2/issue/JR-1?fields=summary,description,status,fixVersions HTTP/1.1"; 2]);
+}
+
+#[test]
+fn finds_current_version_in_parameter_file() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        bump = "minor"
+        current-version-source = { type = "file", file = "parameters.json", path = "/uat/app/version" }
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        versions = "{now-version} -> {next-version}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    std::fs::write(tmp_dir.path().join("parameters.json"),
+                   r#"{"/uat/app/version": "1.4.2", "/uat/other/version": "x.y"}"#).unwrap();
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"versions\":\"1.4.2 -> 1.5.0\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-c").arg("2.0.0");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"versions\":\"2.0.0 -> 2.1.0\""));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf.replace("/uat/app/", "/uat/other/"));
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with("error: Parameter '/uat/other/version': Invalid version 'x.y'"));
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf.replace("/uat/app/", "/prod/app/"));
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with(format!(
+        "error: Parameter '/prod/app/version' not found in {}", tmp_dir.path().join("parameters.json").display())));
+}
+
+#[test]
+#[cfg(unix)]
+fn finds_current_version_in_ssm() {
+    use std::os::unix::fs::PermissionsExt;
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        current-version-source = { type = "ssm", path = "/uat/app/version", region = "eu-west-2" }
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        versions = "{now-version} -> {next-version}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let bin = tmp_dir.path().join("bin");
+    std::fs::create_dir(&bin).unwrap();
+    std::fs::write(bin.join("aws"), indoc!(r#"
+        #!/bin/sh
+        echo "$@" > "$(dirname "$0")/args"
+        case "$*" in
+            *missing*) echo "An error occurred (ParameterNotFound) when calling the GetParameter operation" >&2; exit 254;;
+        esac
+        echo '{"Parameter": {"Name": "/uat/app/version", "Value": "2.0.0", "Type": "String"}}'
+    "#)).unwrap();
+    std::fs::set_permissions(bin.join("aws"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default());
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.env("PATH", &path).arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"versions\":\"2.0.0 -> 2.0.1\""));
+    assert_eq!(std::fs::read_to_string(bin.join("args")).unwrap(),
+               "ssm get-parameter --with-decryption --output json --name /uat/app/version --region eu-west-2\n");
+
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), &conf.replace("/uat/app/", "/uat/missing/"));
+    cmd.env("PATH", &path).arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with("error: Parameter '/uat/missing/version' not found in SSM"));
+}
+
+#[test]
+fn composes_tweet_from_git_commit_subjects() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tweet-template = "{name} {next-version}: {items | join(\", \")}"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tweet = "{tweet}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "initial import");
+    git(tmp_dir.path(), &["tag", "v1"]);
+    commit(tmp_dir.path(), "feat: add login\n\nWith a long description");
+    commit(tmp_dir.path(), "Merge branch 'login'");
+    commit(tmp_dir.path(), "fix: crash on start");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-n").arg("1.1.0").arg("--tweet-from").arg("git");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"tweet\":\"a_release 1.1.0: feat: add login, fix: crash on start\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--tweet-from").arg("git").arg("-t").arg("given tweet");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"tweet\":\"given tweet\""));
+
+    git(tmp_dir.path(), &["tag", "v2"]);
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--tweet-from").arg("git");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: No commit subjects to compose the tweet from, give the tweet with -t"));
+}
+
+#[test]
+fn composes_tweet_from_jira_summaries_and_file_within_max_length() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tracker = { type = "dir", path = "issues" }
+        tweet-file = "notes.txt"
+        tweet-max-length = 40
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tweet = "{tweet}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    std::fs::create_dir(tmp_dir.path().join("issues")).unwrap();
+    write_issue(tmp_dir.path(), "JR-1", "Add login", "Done", &[]);
+    write_issue(tmp_dir.path(), "JR-2", "Fix crash", "Done", &[]);
+    std::fs::write(tmp_dir.path().join("notes.txt"), "First change\n\nSecond change\nThird change\n").unwrap();
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("-j").arg("JR-1").arg("JR-2").arg("--tweet-from").arg("jira");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"tweet\":\"Add login; Fix crash\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--tweet-from").arg("file");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"tweet\":\"First change; Second change; +1 more\""));
+
+    std::fs::write(tmp_dir.path().join("notes.txt"), "A very long change that does not fit in a tweet\n").unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--tweet-from").arg("file");
+    cmd.assert().success().stdout(predicate::str::starts_with("[{\"tweet\":\"A very long change that does not fit…\""));
+
+    std::fs::write(tmp_dir.path().join("notes.txt"), "\n").unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--tweet-from").arg("file");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: No lines in the tweet file to compose the tweet from, give the tweet with -t"));
+}
+
+#[test]
+fn validate_tweet_sources_and_template() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--tweet-from").arg("slack");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: Unknown tweet source 'slack' (available sources: git, jira, file)"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--tweet-from").arg("file");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: The --tweet-from file option requires a release tweet-file"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--tweet-from").arg("jira");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: The --tweet-from jira option requires a release tracker"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tweet-template = "{itemz}"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        ".templar.toml:3:18: Release 'a_release': Unknown placeholder '{itemz}' at 'tweet-template'"));
+}
+
+#[test]
+fn asks_for_missing_release_values_interactively() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        current = "{current-version}"
+        next = "{next-version}"
+        tweet = "{tweet}"
+        pvt = "{pvt-line-range}"
+        main = "{main-class}"
+        jiras = []
+        release-date = ""
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--interactive");
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin("1.4.2\n1.4\n\nJR-1 bad, JR-2\nJR-1\n\nJR-3\n\n20-10\n5-15\n\n\nA tweet\n2020-01-01 10:00\n")
+        .assert().success()
+        .stdout(predicate::str::similar(concat!(
+            "[{\"current\":\"1.4.2\",\"next\":\"1.4.3\",\"tweet\":\"A tweet\",\"pvt\":\"5-15\",\"main\":\"\",",
+            "\"jiras\":[\"JR-1\",\"JR-2\"],\"release-date\":\"2020-01-01 10:00:00\",\"wip-jiras\":[\"JR-3\"]}]\n")))
+        .stderr(predicate::str::contains("Current version: Next version [1.4.3]: ")
+            .and(predicate::str::contains("Next version '1.4' must be greater than current version '1.4.2'"))
+            .and(predicate::str::contains("Invalid jira 'bad', expected a key matching jira-pattern"))
+            .and(predicate::str::contains("Invalid PVT line range '20-10', expected <start>-<end>"))
+            .and(predicate::str::contains("Release date [now]: ")));
+}
+
+#[test]
+fn asks_for_versions_that_can_not_be_derived() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        tag-pattern = "v{version}"
+        current-version-source = { type = "file", file = "missing.json", path = "/version" }
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        versions = "{current-version} {next-version}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    git(tmp_dir.path(), &["init", "-q"]);
+    commit(tmp_dir.path(), "initial import");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release").arg("--interactive");
+    assert_cmd::Command::from_std(cmd).write_stdin("1.0.0\n\n\n\n1-2\n\na tweet\n\n")
+        .assert().success().stdout(predicate::str::starts_with("[{\"versions\":\"1.0.0 1.0.1\""))
+        .stderr(predicate::str::contains("Current version: Next version [1.0.1]: "));
+}
+
+#[test]
+fn interactive_release_fails_without_required_values() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--interactive");
+    assert_cmd::Command::from_std(cmd).write_stdin("")
+        .assert().failure().stderr(predicate::str::contains("error: No value given for the current version"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--interactive").arg("-c").arg("1").arg("-n").arg("2").arg("-j").arg("JR-1")
+        .arg("-p").arg("1-2").arg("--main-class").arg("Main").arg("--release-date").arg("now");
+    assert_cmd::Command::from_std(cmd).write_stdin("")
+        .assert().failure().stderr(predicate::str::contains("error: No value given for the tweet"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        interactive = true
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        tweet = "{tweet}"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    assert_cmd::Command::from_std(cmd).write_stdin("")
+        .assert().success().stdout(predicate::str::starts_with("[{\"tweet\":\"default tweet\""))
+        .stderr(concat!(
+            "warning: No current-version for release 'a_release', using '1' (use -c, --current or declare its default in [[releases.parameters]])\n",
+            "warning: No next-version for release 'a_release', using '2' (use -n, --next or declare its default in [[releases.parameters]])\n",
+            "warning: No pvt-line-range for release 'a_release', using '10-20' (use -p, --pvt-line-range or declare its default in [[releases.parameters]])\n",
+            "warning: No tweet for release 'a_release', using 'default tweet' (use -t, --tweet or declare its default in [[releases.parameters]])\n"));
+}
+
+#[test]
+fn interactive_release_validates_options_before_asking() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_default_conf(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--interactive").arg("--format").arg("yml");
+    assert_cmd::Command::from_std(cmd).write_stdin("1.0.0\n")
+        .assert().failure().stderr(predicate::str::starts_with("error: Unknown format 'yml'"));
+
+    for (option, error) in [("--split", "error: The --split option requires --output <dir>"),
+                            ("--strict", "error: The --strict option requires --check")] {
+        let mut cmd = templar_cmd(tmp_dir.path());
+        cmd.arg("release").arg("test").arg("--interactive").arg(option);
+        assert_cmd::Command::from_std(cmd).write_stdin("1.0.0\n")
+            .assert().failure().stderr(predicate::str::starts_with(error));
+    }
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("test").arg("--interactive").arg("--tweet-from").arg("slack");
+    assert_cmd::Command::from_std(cmd).write_stdin("1.0.0\n")
+        .assert().failure().stderr(predicate::str::starts_with("error: Unknown tweet source 'slack'"));
+}
+
+#[test]
+fn applies_release_parameters() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.parameters]]
+        name = "tweet"
+        required = true
+        help = "Announcement of the release"
+        [[releases.parameters]]
+        name = "current-version"
+        default = "1.4.2"
+        [[releases.parameters]]
+        name = "pvt-line-range"
+        default = "1-{next-version.patch}"
+        pattern = "^\\d+-\\d+$"
+        [[releases.parameters]]
+        name = "main-class"
+        default = "com.example.{name | upper}"
+        [[releases.parameters]]
+        name = "jiras"
+        default = "JR-1, JR-2"
+        [[releases.parameters]]
+        name = "release-date"
+        default = "2020-01-01 10:00"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        current = "{current-version}"
+        next = "{next-version}"
+        tweet = "{tweet}"
+        pvt = "{pvt-line-range}"
+        main = "{main-class}"
+        jiras = []
+        release-date = ""
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Missing required parameter 'tweet' of release 'a_release', use -t, --tweet or --interactive"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-t").arg("A tweet");
+    cmd.assert().success().stdout(predicate::str::starts_with(concat!(
+        "[{\"current\":\"1.4.2\",\"next\":\"1.4.3\",\"tweet\":\"A tweet\",\"pvt\":\"1-3\",",
+        "\"main\":\"com.example.A_RELEASE\",\"jiras\":[\"JR-1\",\"JR-2\"],\"release-date\":\"2020-01-01 10:00:00\"")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("-t").arg("A tweet").arg("-p").arg("x-1");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Parameter 'pvt-line-range': 'x-1' does not match pattern '^\\d+-\\d+$'"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--interactive").arg("-n").arg("2.0.0");
+    assert_cmd::Command::from_std(cmd).write_stdin("\n\n\n\n\nA tweet\n\n")
+        .assert().success()
+        .stdout(predicate::str::starts_with(concat!(
+            "[{\"current\":\"1.4.2\",\"next\":\"2.0.0\",\"tweet\":\"A tweet\",\"pvt\":\"1-0\",",
+            "\"main\":\"com.example.A_RELEASE\",\"jiras\":[\"JR-1\",\"JR-2\"],\"release-date\":\"2020-01-01 10:00:00\"")))
+        .stderr(predicate::str::contains("Current version [1.4.2]: ")
+            .and(predicate::str::contains("Jiras (separated by spaces, an empty line to finish) [JR-1 JR-2]:"))
+            .and(predicate::str::contains("PVT line range [1-{next-version.patch}]: ")));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--help");
+    cmd.assert().success().stdout(predicate::str::contains(concat!(
+        "Parameters of release 'a_release':\n",
+        "    tweet (-t, --tweet)             Announcement of the release [required]\n",
+        "    current-version (-c, --current) [default: 1.4.2]\n",
+        "    pvt-line-range (-p, --pvt-line-range) [default: 1-{next-version.patch}] [pattern: ^\\d+-\\d+$]\n")));
+}
+
+#[test]
+fn validate_release_parameters() {
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.parameters]]
+        name = "build-number"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "unknown parameter 'build-number' (available parameters: current-version, next-version, tweet, "));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.parameters]]
+        name = "next-version"
+        default = "next"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid default of parameter 'next-version': Invalid version 'next'"));
+
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [[releases.parameters]]
+        name = "tweet"
+        default = "{nam}"
+        [[releases.parameters]]
+        name = "tweet"
+        [[releases.templates]]
+        id = "default"
+    "#);
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        ".templar.toml:5:11: Release 'a_release': Unknown placeholder '{nam}' at 'parameters.tweet.default'")
+        .and(predicate::str::contains(
+            ".templar.toml:7:8: Duplicate parameter 'tweet' in release 'a_release', first defined at line 4")));
+}
+
+#[test]
+fn renders_release_variables() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [releases.vars]
+        environment = "dev"
+        build-number = 0
+        region = "LDN"
+        owner = "team"
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        env = "{environment}"
+        build = "{build-number}"
+        region = "{region | lower}"
+        owner = "{owner}"
+        title = "{name} {next-version} on {environment}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("release").arg("a_release");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"env\":\"dev\",\"build\":0,\"region\":\"ldn\",\"owner\":\"team\",\"title\":\"a_release 2 on dev\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.env("TEMPLAR_VAR_BUILD_NUMBER", "7");
+    cmd.arg("release").arg("a_release").arg("--var").arg("region=NYC");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"env\":\"dev\",\"build\":7,\"region\":\"nyc\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--var").arg("build-number=42");
+    cmd.assert().success().stdout(predicate::str::contains("\"build\":42,"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.env("TEMPLAR_VAR_BUILD_NUMBER", "seven");
+    cmd.arg("release").arg("a_release");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: TEMPLAR_VAR_BUILD_NUMBER value 'seven' is not a number like the default 0"));
+
+    std::fs::write(tmp_dir.path().join("vars.toml"), "owner = \"file owner\"\nbuild-number = 42\nother = 1\n").unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--vars-file").arg("vars.toml");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Unknown variable 'other' of release 'a_release' (declared variables: environment, build-number, region, owner) \
+         in vars file 'vars.toml'"));
+
+    std::fs::write(tmp_dir.path().join("vars.toml"), "owner = \"file owner\"\nbuild-number = 42\n").unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.env("TEMPLAR_VAR_REGION", "NYC").env("TEMPLAR_VAR_OWNER", "env owner").env("TEMPLAR_VAR_BUILD_NUMBER", "7");
+    cmd.arg("release").arg("a_release").arg("--vars-file").arg("vars.toml")
+        .arg("--var").arg("environment=prod").arg("--var").arg("owner=cli owner");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "[{\"env\":\"prod\",\"build\":42,\"region\":\"nyc\",\"owner\":\"cli owner\",\"title\":\"a_release 2 on prod\""));
+
+    std::fs::write(tmp_dir.path().join("vars.json"), "{\"build-number\": \"1.2\"}").unwrap();
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.env("TEMPLAR_VAR_BUILD_NUMBER", "7");
+    cmd.arg("release").arg("a_release").arg("--vars-file").arg("vars.json");
+    cmd.assert().success().stdout(predicate::str::contains("\"build\":\"1.2\""));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--var").arg("build=1");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Unknown variable 'build' of release 'a_release' (declared variables: environment, build-number, region, owner)"));
+
+    let mut cmd = templar_cmd(tmp_dir.path());
+    cmd.arg("release").arg("a_release").arg("--var").arg("environment");
+    cmd.assert().failure().stderr(predicate::str::starts_with(
+        "error: Invalid variable 'environment', expected --var <name>=<value>"));
+}
+
+#[test]
+fn validate_release_variables() {
+    let conf = indoc!(r#"
+        [[releases]]
+        name = "a_release"
+        [releases.vars]
+        tweet = "a tweet"
+        "build.number" = 1
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        env = "{environment}"
+    "#);
+    let tmp_dir = TempDir::new().expect("temp_dir failed");
+    let mut cmd = templar_cmd_with_conf(tmp_dir.path(), conf);
+    cmd.arg("config").arg("validate");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        ".templar.toml:4:1: Release 'a_release': variable 'tweet' shadows the placeholder '{tweet}'")
+        .and(predicate::str::contains(
+            ".templar.toml:5:2: Release 'a_release': invalid variable name 'build.number', expected letters, digits, '-' or '_'"))
+        .and(predicate::str::contains(
+            ".templar.toml:9:8: Template 'default': Unknown placeholder '{environment}' at 'content.env'")));
+}
+
+fn templar_cmd_with_default_conf(home_dir: &Path) -> Command {
+    let conf = indoc!(r#"
+        # Templar Configuration
+
+        [[releases]]
+        name = "test"
+
+        [[releases.templates]]
+        id = "default"
+        [releases.templates.content]
+        region = "LDN"
+        nowVersion = "1"
+        nextVersion = "2"
+        tweet = "default tweet"
+    "#);
+    templar_cmd_with_conf(home_dir, conf)
+}
+
+fn templar_cmd_with_conf(home_dir: &Path, conf: &'_ str) -> Command {
+    let tmp_dir = home_dir.to_str().unwrap();
+    let conf_file: PathBuf = [tmp_dir, ".templar.toml"].iter().collect();
+    std::fs::write(conf_file, conf).unwrap();
+    templar_cmd(home_dir)
+}
+
+fn templar_cmd(home_dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("templar").unwrap();
+    cmd.current_dir(home_dir).env("TEMPLAR_SYSTEM_CONF", home_dir.join("system.toml"));
+    cmd.arg("--home").arg(home_dir);
+    cmd
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git").args(args).current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "templar").env("GIT_AUTHOR_EMAIL", "templar@example.com")
+        .env("GIT_COMMITTER_NAME", "templar").env("GIT_COMMITTER_EMAIL", "templar@example.com")
+        .status().expect("git failed");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn commit(dir: &Path, message: &str) {
+    git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+}
+
+fn commit_file(dir: &Path, file: &str, content: &str) {
+    let path = dir.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+    git(dir, &["add", file]);
+    commit(dir, &format!("Update {}", file));
+}
+
+fn write_issue(dir: &Path, key: &str, summary: &str, status: &str, fix_versions: &[&str]) {
+    let issue = serde_json::json!({
+        "key": key,
+        "fields": {
+            "summary": summary,
+            "description": format!("{} description", summary),
+            "status": { "name": status },
+            "fixVersions": fix_versions.iter().map(|name| serde_json::json!({ "name": name })).collect::<Vec<_>>(),
+        }
+    });
+    std::fs::write(dir.join("issues").join(format!("{}.json", key)), issue.to_string()).unwrap();
+}